@@ -4,8 +4,9 @@ use inkwell::context::Context;
 use inkwell::OptimizationLevel;
 use inkwell::execution_engine::JitFunction;
 use crate::args::RunArgs;
-use cmpler_core::driver::{compile, compile_to_object, link_executable};
+use cmpler_core::driver::{compile_for_target, compile_to_object, link_executable};
 use cmpler_core::codegen::llvm_gen::LLVMCodeGen;
+use cmpler_core::codegen::target::host_target_machine;
 use cmpler_core::error::CompilerError;
 
 // For now it just uses LLVM ExecutionEngine
@@ -14,9 +15,10 @@ pub fn launch_run(args: &RunArgs) -> Result<(), CompilerError> {
         .map_err(CompilerError::Io)?;
 
     if args.jit {
-        let program = compile(&source)?;
+        let tm = host_target_machine(OptimizationLevel::Default).map_err(|e| CompilerError::Codegen(e.to_string()))?;
+        let program = compile_for_target(&source, &tm)?;
         let context = Context::create();
-        let module = LLVMCodeGen::compile_program(&context, &program, OptimizationLevel::Default);
+        let module = LLVMCodeGen::compile_program(&context, &program, OptimizationLevel::Default, &tm);
         let ee = module.create_jit_execution_engine(OptimizationLevel::Default)
             .map_err(|e| CompilerError::Codegen(format!("Failed to create JIT engine: {:?}", e)))?;
 
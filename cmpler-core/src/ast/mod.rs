@@ -1,3 +1,3 @@
 pub mod nodes;
 
-pub use nodes::{Program, Decl, Stmt, Expr};
+pub use nodes::{Program, Decl, Stmt, Expr, StorageClass};
@@ -1,22 +1,62 @@
 use crate::utils::span::Span;
 use crate::lexer::TokenKind;
+use crate::semantic::symbol_table::Type;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Program {
     pub decls: Vec<Decl>,
 }
 
+/// A storage class specifier. File-scope declarations without one have
+/// external linkage; block-scope ones are automatic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageClass {
+    /// Internal linkage at file scope; static storage duration in a block.
+    Static,
+    /// Refers to a definition elsewhere, possibly in another translation unit.
+    Extern,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Decl {
     Function {
         name: String,
-        params: Vec<String>,
-        body: Vec<Stmt>,
+        ret_ty: Type,
+        params: Vec<(String, Type)>,
+        /// Whether the parameter list ends in `...`.
+        variadic: bool,
+        /// `None` for a prototype such as `int putchar(int c);`.
+        body: Option<Vec<Stmt>>,
+        storage: Option<StorageClass>,
+        span: Span,
+    },
+    /// A file-scope definition such as `struct point { int x; int y; };`, or of
+    /// a union, whose members all start at offset 0.
+    Struct {
+        name: String,
+        members: Vec<(String, Type)>,
+        is_union: bool,
+        span: Span,
+    },
+    /// A file-scope `enum` definition with the value of each constant.
+    Enum {
+        name: Option<String>,
+        constants: Vec<(String, i64)>,
+        span: Span,
+    },
+    /// `typedef ty name;`. The parser already substitutes `ty` wherever `name` is used.
+    Typedef {
+        name: String,
+        ty: Type,
         span: Span,
     },
+    /// A global variable; without an initializer it is zero-initialized, unless
+    /// it is `extern` and only declares a variable defined elsewhere.
     Var {
         name: String,
-        init: Box<Expr>,
+        ty: Type,
+        init: Option<Box<Expr>>,
+        storage: Option<StorageClass>,
         span: Span,
     },
 }
@@ -24,32 +64,53 @@ pub enum Decl {
 impl Decl {
     pub fn span(&self) -> Span {
         match self {
-            Decl::Function { span, .. } | Decl::Var { span, .. } => *span,
+            Decl::Function { span, .. }
+            | Decl::Struct { span, .. }
+            | Decl::Enum { span, .. }
+            | Decl::Typedef { span, .. }
+            | Decl::Var { span, .. } => *span,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
-    Return(Box<Expr>),
+    Return(Option<Box<Expr>>, Span),
     If {
         cond: Box<Expr>,
         then_block: Vec<Stmt>,
         else_block: Option<Vec<Stmt>>,
     },
     While(Box<Expr>, Vec<Stmt>),
+    DoWhile(Vec<Stmt>, Box<Expr>),
     For {
-        init: Option<Box<Expr>>,
+        /// Empty, a single `Stmt::Expr`, or the `LocalVar`s of a declaration
+        /// scoped to the loop.
+        init: Vec<Stmt>,
         cond: Option<Box<Expr>>,
         inc: Option<Box<Expr>>,
         body: Vec<Stmt>,
     },
+    Switch {
+        cond: Box<Expr>,
+        body: Vec<Stmt>,
+    },
+    /// `case value:` label; only valid directly inside a `switch` body.
+    Case(Box<Expr>, Span),
+    /// `default:` label; only valid directly inside a `switch` body.
+    Default(Span),
+    Break(Span),
+    Continue(Span),
     Block(Vec<Stmt>),
     Expr(Box<Expr>),
 
+    /// A block-scope declaration. A `static` one lives for the whole program and
+    /// is initialized once; an `extern` one refers to a global.
     LocalVar {
         name: String,
-        init: Expr,
+        ty: Type,
+        init: Option<Expr>,
+        storage: Option<StorageClass>,
         span: Span,
     },
 
@@ -58,28 +119,111 @@ pub enum Stmt {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
+    /// An integer constant; `ty` is the type given by its magnitude and suffix.
     IntLiteral {
         value: i64,
+        ty: Type,
+        span: Span,
+    },
+    /// A floating constant of type `float` or `double`.
+    FloatLiteral {
+        value: f64,
+        ty: Type,
+        span: Span,
+    },
+    /// An explicit conversion `(ty)expr`.
+    Cast {
+        ty: Type,
+        expr: Box<Expr>,
+        span: Span,
+    },
+    /// `sizeof(type)`.
+    SizeofType {
+        ty: Type,
+        span: Span,
+    },
+    /// `sizeof expr`. The operand is only used for its type and is not evaluated.
+    SizeofExpr {
+        expr: Box<Expr>,
+        span: Span,
+    },
+    /// The bytes of a string literal, escapes decoded, without the terminating NUL.
+    StringLiteral {
+        value: Vec<u8>,
         span: Span,
     },
     Var {
         name: String,
         span: Span,
     },
+    Unary {
+        op: TokenKind,
+        operand: Box<Expr>,
+        span: Span,
+    },
+    /// Postfix `++` or `--`; evaluates to the operand's value before the update.
+    Postfix {
+        op: TokenKind,
+        operand: Box<Expr>,
+        span: Span,
+    },
     Binary {
         op: TokenKind,
         left: Box<Expr>,
         right: Box<Expr>,
         span: Span,
     },
+    /// `cond ? then_expr : else_expr`
+    Conditional {
+        cond: Box<Expr>,
+        then_expr: Box<Expr>,
+        else_expr: Box<Expr>,
+        span: Span,
+    },
+    Call {
+        callee: String,
+        args: Vec<Expr>,
+        span: Span,
+    },
+    /// `base[index]`, equivalent to `*(base + index)`.
+    Index {
+        base: Box<Expr>,
+        index: Box<Expr>,
+        span: Span,
+    },
+    /// `base.member`, or `base->member` when `arrow` is set.
+    Member {
+        base: Box<Expr>,
+        member: String,
+        arrow: bool,
+        span: Span,
+    },
+    /// A brace-enclosed initializer such as `{1, 2, 3}`; only valid as the
+    /// initializer of a declaration or as an element of another list.
+    InitList {
+        items: Vec<Expr>,
+        span: Span,
+    },
 }
 
 impl Expr {
     pub fn span(&self) -> Span {
         match self {
             Expr::IntLiteral { span, .. }
+            | Expr::FloatLiteral { span, .. }
+            | Expr::Cast { span, .. }
+            | Expr::SizeofType { span, .. }
+            | Expr::SizeofExpr { span, .. }
+            | Expr::StringLiteral { span, .. }
             | Expr::Var { span, .. }
-            | Expr::Binary { span, .. } => *span,
+            | Expr::Unary { span, .. }
+            | Expr::Postfix { span, .. }
+            | Expr::Binary { span, .. }
+            | Expr::Conditional { span, .. }
+            | Expr::Call { span, .. }
+            | Expr::Index { span, .. }
+            | Expr::Member { span, .. }
+            | Expr::InitList { span, .. } => *span,
         }
     }
 }
@@ -1,13 +1,18 @@
 use inkwell::context::Context;
+use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
-use inkwell::module::Module;
-use inkwell::types::IntType;
-use inkwell::values::{BasicValueEnum, FunctionValue, PointerValue};
+use inkwell::module::{Linkage, Module};
+use inkwell::types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, IntType};
+use inkwell::values::{ArrayValue, BasicMetadataValueEnum, BasicValue, BasicValueEnum, FunctionValue, GlobalValue, IntValue, PointerValue};
 use inkwell::passes::PassManager;
-use inkwell::OptimizationLevel;
-use std::collections::HashMap;
-use crate::ast::nodes::{Program, Decl, Stmt, Expr};
+use inkwell::targets::{TargetData, TargetMachine};
+use inkwell::{AddressSpace, FloatPredicate, IntPredicate, OptimizationLevel};
+use std::collections::{HashMap, HashSet};
+use crate::ast::nodes::{Program, Decl, Stmt, Expr, StorageClass};
 use crate::lexer::TokenKind;
+use crate::semantic::const_eval::{eval_const, eval_const_float, wrap};
+use crate::semantic::layout::TargetLayout;
+use crate::semantic::symbol_table::Type;
 
 pub struct LLVMCodeGen<'ctx> {
     context: &'ctx Context,
@@ -15,8 +20,24 @@ pub struct LLVMCodeGen<'ctx> {
     builder: Builder<'ctx>,
     i32_type: IntType<'ctx>,
     function: Option<FunctionValue<'ctx>>,
-    variables: HashMap<String, PointerValue<'ctx>>,
+    /// Local variables by scope, innermost last, with their declared types.
+    variables: Vec<HashMap<String, (PointerValue<'ctx>, Type)>>,
+    /// Types of global variables and functions.
+    globals: HashMap<String, Type>,
+    /// Members of each struct, by tag, in declaration order.
+    structs: HashMap<String, Vec<(String, Type)>>,
+    /// Return type of the function being generated.
+    ret_ty: Type,
+    /// Globals holding the string literals emitted so far, keyed by contents.
+    strings: HashMap<Vec<u8>, PointerValue<'ctx>>,
+    /// Jump targets of the enclosing loops and switches, innermost last.
+    break_targets: Vec<BasicBlock<'ctx>>,
+    continue_targets: Vec<BasicBlock<'ctx>>,
     pass_manager: PassManager<Module<'ctx>>,
+    /// Layout of the machine the module is compiled for, which `sizeof` reads.
+    target_data: TargetData,
+    /// The same layout as the front end sees it, which decides how wide `long` is.
+    layout: TargetLayout,
 }
 
 impl<'ctx> LLVMCodeGen<'ctx> {
@@ -24,12 +45,20 @@ impl<'ctx> LLVMCodeGen<'ctx> {
         context: &'ctx Context,
         module_name: &str,
         opt_level: OptimizationLevel,
+        target: &TargetMachine,
     ) -> Self {
         let module = context.create_module(module_name);
         let builder = context.create_builder();
         let i32_type = context.i32_type();
         let pass_manager = PassManager::create(());
 
+        // Struct layout follows the target's data layout, so member offsets and
+        // sizes agree with code built by `cc`.
+        let target_data = target.get_target_data();
+        let layout = TargetLayout::new(&target_data);
+        module.set_triple(&target.get_triple());
+        module.set_data_layout(&target_data.get_data_layout());
+
         if opt_level != OptimizationLevel::None {
             pass_manager.add_instruction_combining_pass();
             pass_manager.add_reassociate_pass();
@@ -43,8 +72,16 @@ impl<'ctx> LLVMCodeGen<'ctx> {
             builder,
             i32_type,
             function: None,
-            variables: HashMap::new(),
+            variables: Vec::new(),
+            globals: HashMap::new(),
+            structs: HashMap::new(),
+            ret_ty: Type::Void,
+            strings: HashMap::new(),
+            break_targets: Vec::new(),
+            continue_targets: Vec::new(),
             pass_manager,
+            target_data,
+            layout,
         }
     }
 
@@ -52,8 +89,9 @@ impl<'ctx> LLVMCodeGen<'ctx> {
         context: &'ctx Context,
         program: &Program,
         opt_level: OptimizationLevel,
+        target: &TargetMachine,
     ) -> Module<'ctx> {
-        let mut gen = LLVMCodeGen::new(context, "cmpler_module", opt_level);
+        let mut gen = LLVMCodeGen::new(context, "cmpler_module", opt_level, target);
         gen.gen_program(program);
         if opt_level != OptimizationLevel::None {
             gen.pass_manager.run_on(&gen.module);
@@ -62,100 +100,689 @@ impl<'ctx> LLVMCodeGen<'ctx> {
     }
 
     fn gen_program(&mut self, program: &Program) {
+        // Declare every function up front so calls may refer to functions defined later.
+        // Functions that only have a prototype stay external declarations, resolved by
+        // the linker or the JIT. The first declaration of a name decides its linkage;
+        // the analyzer has checked that later ones agree.
+        //
+        // Struct and union types are named before anything else, as a prototype may
+        // mention one before its definition.
+        //
+        // A variable declared without an initializer or `extern` is a tentative
+        // definition, emitted as zero unless another declaration gives it a value.
+        // Likewise a function declared with `()` takes its parameters from another
+        // declaration if there is one. Every variable is declared before any is
+        // given its value, since an initializer may take the address of a
+        // variable declared later.
+        let mut initialized = HashSet::new();
+        let mut prototyped = HashSet::new();
+        let mut internal = HashMap::new();
+        for decl in &program.decls {
+            match decl {
+                Decl::Function { name, params, variadic, storage, .. } => {
+                    internal.entry(name.as_str()).or_insert(*storage == Some(StorageClass::Static));
+                    if !params.is_empty() || !*variadic {
+                        prototyped.insert(name.as_str());
+                    }
+                }
+                Decl::Struct { name, is_union, .. } => {
+                    let keyword = if *is_union { "union" } else { "struct" };
+                    self.context.opaque_struct_type(&format!("{}.{}", keyword, name));
+                }
+                Decl::Var { name, ty, init, storage, .. } => {
+                    internal.entry(name.as_str()).or_insert(*storage == Some(StorageClass::Static));
+                    if init.is_some() {
+                        initialized.insert(name.as_str());
+                    }
+                    // `extern int a[];` leaves the size to another declaration.
+                    if !matches!(ty, Type::Array(_, 0)) || !self.globals.contains_key(name) {
+                        self.globals.insert(name.clone(), ty.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        for decl in &program.decls {
+            match decl {
+                Decl::Struct { name, members, is_union: false, .. } => {
+                    let member_types: Vec<BasicTypeEnum> = members.iter().map(|(_, ty)| self.llvm_type(ty)).collect();
+                    self.llvm_type(&Type::Struct(name.clone())).into_struct_type().set_body(&member_types, false);
+                    self.structs.insert(name.clone(), members.clone());
+                }
+                Decl::Struct { name, members, is_union: true, .. } => {
+                    let body = self.union_body(members);
+                    self.llvm_type(&Type::Union(name.clone())).into_struct_type().set_body(&body, false);
+                    self.structs.insert(name.clone(), members.clone());
+                }
+                // Enumeration constants were folded into literals by the parser and
+                // typedef names resolved to their types, so neither emits anything.
+                Decl::Enum { .. } | Decl::Typedef { .. } => {}
+                Decl::Function { name, params, variadic, .. }
+                    if self.module.get_function(name).is_some() || (params.is_empty() && *variadic && prototyped.contains(name.as_str())) => {}
+                Decl::Function { name, ret_ty, params, variadic, .. } => {
+                    let param_tys: Vec<Type> = params.iter().map(|(_, ty)| ty.clone()).collect();
+                    let fn_type = self.fn_type(ret_ty, &param_tys, *variadic);
+                    let linkage = internal[name.as_str()].then_some(Linkage::Internal);
+                    self.module.add_function(name, fn_type, linkage);
+                    let ty = Type::Function { ret: Box::new(ret_ty.clone()), params: param_tys, variadic: *variadic };
+                    self.globals.insert(name.clone(), ty);
+                }
+                Decl::Var { name, .. } if self.module.get_global(name).is_some() => {}
+                Decl::Var { name, .. } => {
+                    let global = self.module.add_global(self.llvm_type(&self.globals[name]), None, name);
+                    if internal[name.as_str()] {
+                        global.set_linkage(Linkage::Internal);
+                    }
+                }
+            }
+        }
+        // Variables that are only ever declared `extern` get no initializer and are
+        // left for the linker.
         for decl in &program.decls {
-            if let Decl::Function { .. } = decl {
+            if let Decl::Var { name, init, storage, .. } = decl {
+                let global = self.module.get_global(name).unwrap();
+                let tentative = init.is_none() && *storage != Some(StorageClass::Extern) && !initialized.contains(name.as_str());
+                if init.is_some() || (tentative && global.get_initializer().is_none()) {
+                    let ty = self.globals[name].clone();
+                    self.define_global(global, &ty, init.as_deref());
+                }
+            }
+        }
+        for decl in &program.decls {
+            if let Decl::Function { body: Some(_), .. } = decl {
                 self.gen_function(decl);
             }
         }
     }
 
     fn gen_function(&mut self, decl: &Decl) {
-        if let Decl::Function { name, body, .. } = decl {
-            let fn_val = self.module.add_function(
-                name,
-                self.i32_type.fn_type(&[], false),
-                None,
-            );
+        if let Decl::Function { name, ret_ty, params, body: Some(body), .. } = decl {
+            let fn_val = self.module.get_function(name).expect("function not declared");
             self.function = Some(fn_val);
+            self.ret_ty = ret_ty.clone();
             let entry = self.context.append_basic_block(fn_val, "entry");
             self.builder.position_at_end(entry);
             self.variables.clear();
+            self.enter_scope();
+
+            for ((param, ty), value) in params.iter().zip(fn_val.get_param_iter()) {
+                if param.is_empty() {
+                    continue;
+                }
+                value.set_name(param);
+                let ptr = self.create_entry_alloca(param, ty);
+                self.builder.build_store(ptr, value).expect("store failed");
+                self.declare_local(param, ptr, ty);
+            }
 
             for stmt in body {
                 self.gen_stmt(stmt);
             }
+            self.exit_scope();
 
             if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
-                let _ = self.builder.build_return(Some(&self.i32_type.const_zero()));
+                match fn_val.get_type().get_return_type() {
+                    Some(ty) => { let _ = self.builder.build_return(Some(&ty.const_zero())); }
+                    None => { let _ = self.builder.build_return(None); }
+                }
+            }
+        }
+    }
+
+    /// Gives the declared global variable `global` of type `ty` the constant value
+    /// of `init`, returning the global that now holds it. A `const` one that is
+    /// not also `volatile` becomes an LLVM constant.
+    fn define_global(&mut self, global: GlobalValue<'ctx>, ty: &Type, init: Option<&Expr>) -> GlobalValue<'ctx> {
+        let initializer = self.const_initializer(ty, init);
+        let mut global = global;
+        // An initializer with a layout of its own (see `const_packed`) needs a
+        // global of that type, which takes over the declared one's uses and name.
+        // It has byte alignment, so the declared type's alignment is kept explicitly.
+        if initializer.get_type() != self.llvm_type(ty) {
+            let name = global.get_name().to_str().unwrap().to_string();
+            let replacement = self.module.add_global(initializer.get_type(), None, &name);
+            replacement.set_linkage(global.get_linkage());
+            replacement.set_alignment(self.target_data.get_abi_alignment(&self.llvm_type(ty)));
+            global.as_pointer_value().replace_all_uses_with(replacement.as_pointer_value());
+            // SAFETY: the declared global has no uses left and is not referenced again.
+            unsafe { global.delete() };
+            replacement.set_name(&name);
+            global = replacement;
+        }
+        global.set_initializer(&initializer);
+        let qualifiers = ty.qualifiers();
+        global.set_constant(qualifiers.is_const && !qualifiers.is_volatile);
+        global
+    }
+
+    /// Allocas are placed at the start of the entry block so that mem2reg can promote them
+    /// and locals declared inside loops do not grow the stack on every iteration.
+    fn create_entry_alloca(&self, name: &str, ty: &Type) -> PointerValue<'ctx> {
+        let entry = self.function.unwrap().get_first_basic_block().unwrap();
+        let tmp = self.context.create_builder();
+        match entry.get_first_instruction() {
+            Some(instr) => tmp.position_before(&instr),
+            None => tmp.position_at_end(entry),
+        }
+        tmp.build_alloca(self.llvm_type(ty), name).expect("alloca failed")
+    }
+
+    fn llvm_type(&self, ty: &Type) -> BasicTypeEnum<'ctx> {
+        match ty {
+            Type::Pointer(_) => self.context.ptr_type(AddressSpace::default()).into(),
+            Type::Array(elem, len) => self.llvm_type(elem).array_type(*len as u32).into(),
+            Type::Struct(tag) => self.context.get_struct_type(&format!("struct.{}", tag)).expect("undefined struct").into(),
+            Type::Union(tag) => self.context.get_struct_type(&format!("union.{}", tag)).expect("undefined union").into(),
+            Type::Float => self.context.f32_type().into(),
+            Type::Double => self.context.f64_type().into(),
+            Type::Qualified(inner, _) => self.llvm_type(inner),
+            Type::Void | Type::Function { .. } => unreachable!("{:?} is not a value type", ty),
+            integer => self.context.custom_width_int_type(self.layout.int_bits(integer)).into(),
+        }
+    }
+
+    /// LLVM has no unions, so a union is laid out as its most strictly aligned
+    /// member (the largest of those on a tie), padded with bytes up to the size of
+    /// its largest member rounded to that alignment.
+    fn union_body(&self, members: &[(String, Type)]) -> Vec<BasicTypeEnum<'ctx>> {
+        let types: Vec<BasicTypeEnum> = members.iter().map(|(_, ty)| self.llvm_type(ty)).collect();
+        let layout = |ty: &BasicTypeEnum<'ctx>| (self.target_data.get_abi_alignment(ty) as u64, self.target_data.get_abi_size(ty));
+        let Some(&anchor) = types.iter().max_by_key(|ty| layout(ty)) else {
+            return Vec::new();
+        };
+        let align = layout(&anchor).0;
+        let size = types.iter().map(|ty| layout(ty).1).max().unwrap().div_ceil(align) * align;
+        let mut body = vec![anchor];
+        let padding = size - layout(&anchor).1;
+        if padding > 0 {
+            body.push(self.context.i8_type().array_type(padding as u32).into());
+        }
+        body
+    }
+
+    fn fn_type(&self, ret_ty: &Type, params: &[Type], variadic: bool) -> FunctionType<'ctx> {
+        let param_types: Vec<BasicMetadataTypeEnum> = params.iter().map(|ty| self.llvm_type(ty).into()).collect();
+        match ret_ty {
+            Type::Void => self.context.void_type().fn_type(&param_types, variadic),
+            ty => self.llvm_type(ty).fn_type(&param_types, variadic),
+        }
+    }
+
+    fn enter_scope(&mut self) {
+        self.variables.push(HashMap::new());
+    }
+
+    fn exit_scope(&mut self) {
+        self.variables.pop();
+    }
+
+    fn declare_local(&mut self, name: &str, ptr: PointerValue<'ctx>, ty: &Type) {
+        self.variables.last_mut().unwrap().insert(name.to_string(), (ptr, ty.clone()));
+    }
+
+    /// Resolves a variable to its storage and type, searching local scopes
+    /// innermost-first before falling back to module globals.
+    fn lookup_variable(&self, name: &str) -> (PointerValue<'ctx>, Type) {
+        self.variables
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+            .or_else(|| {
+                let global = self.module.get_global(name)?;
+                Some((global.as_pointer_value(), self.globals.get(name)?.clone()))
+            })
+            // A function named as a value is its address.
+            .or_else(|| {
+                let function = self.module.get_function(name)?;
+                Some((function.as_global_value().as_pointer_value(), self.globals.get(name)?.clone()))
+            })
+            .expect("undefined variable")
+    }
+
+    /// Static type of `expr` used as a value, following the same rules as the
+    /// semantic analyzer.
+    fn expr_type(&self, expr: &Expr) -> Type {
+        self.object_type(expr).decay()
+    }
+
+    /// Type of `expr` before array-to-pointer decay.
+    fn object_type(&self, expr: &Expr) -> Type {
+        match expr {
+            Expr::IntLiteral { ty, .. } | Expr::FloatLiteral { ty, .. } | Expr::Cast { ty, .. } => ty.clone(),
+            Expr::SizeofType { .. } | Expr::SizeofExpr { .. } => Type::ULong,
+            Expr::StringLiteral { value, .. } => Type::Array(Box::new(Type::Char), value.len() + 1),
+            Expr::Var { name, .. } => self.lookup_variable(name).1,
+            Expr::Unary { op: TokenKind::PlusPlus | TokenKind::MinusMinus, operand, .. }
+            | Expr::Postfix { operand, .. } => self.expr_type(operand),
+            Expr::Unary { op: TokenKind::LogicalNot, .. } => Type::Int,
+            Expr::Unary { op: TokenKind::Ampersand, operand, .. } => Type::Pointer(Box::new(self.object_type(operand))),
+            Expr::Unary { op: TokenKind::Star, operand, .. } => {
+                self.expr_type(operand).pointee().expect("dereference of a non-pointer").clone()
+            }
+            Expr::Unary { operand, .. } => self.expr_type(operand).promote(),
+            Expr::Binary { op, left, right, .. } => Type::binary_result(op, &self.expr_type(left), &self.expr_type(right)),
+            Expr::Conditional { then_expr, else_expr, .. } => {
+                let (then_ty, else_ty) = (self.expr_type(then_expr), self.expr_type(else_expr));
+                Type::common(&then_ty, &else_ty)
+            }
+            Expr::Call { callee, .. } => match &self.globals[callee] {
+                Type::Function { ret, .. } => (**ret).clone(),
+                _ => unreachable!("call to a non-function"),
+            },
+            Expr::Index { base, index, .. } => {
+                let (base_ty, index_ty) = (self.expr_type(base), self.expr_type(index));
+                let ptr_ty = if base_ty.is_pointer() { base_ty } else { index_ty };
+                ptr_ty.pointee().expect("subscript of a non-pointer").clone()
+            }
+            Expr::Member { base, member, arrow, .. } => {
+                let struct_ty = if *arrow { self.expr_type(base).pointee().unwrap().clone() } else { self.object_type(base) };
+                self.struct_member(&struct_ty, member).1
+            }
+            Expr::InitList { .. } => unreachable!("initializer list used as an expression"),
+        }
+    }
+
+    /// `sizeof ty` as a `size_t` constant, taken from the target's data layout.
+    fn size_of(&self, ty: &Type) -> IntValue<'ctx> {
+        let size = self.target_data.get_abi_size(&self.llvm_type(ty));
+        self.llvm_type(&Type::ULong).into_int_type().const_int(size, false)
+    }
+
+    /// Size in bytes of the operand of a `sizeof` expression, for folding it in
+    /// a constant expression.
+    fn sizeof_value(&self, sizeof: &Expr) -> Option<u64> {
+        let ty = match sizeof {
+            Expr::SizeofType { ty, .. } => ty.clone(),
+            Expr::SizeofExpr { expr, .. } => self.object_type(expr),
+            _ => return None,
+        };
+        Some(self.target_data.get_abi_size(&self.llvm_type(&ty)))
+    }
+
+    /// Index and type of `member` within the struct type `ty`. Members of a
+    /// qualified struct carry its qualifiers.
+    fn struct_member(&self, ty: &Type, member: &str) -> (u32, Type) {
+        let tag = ty.tag().unwrap_or_else(|| unreachable!("member access on {:?}", ty));
+        self.structs[tag]
+            .iter()
+            .enumerate()
+            .find(|(_, (name, _))| name == member)
+            .map(|(i, (_, member_ty))| (i as u32, member_ty.clone().qualified(ty.qualifiers())))
+            .expect("no such member")
+    }
+
+    /// Converts `val` from type `from` to type `to`. Widening sign-extends signed
+    /// types and zero-extends unsigned ones, and floating conversions pick the
+    /// signed or unsigned instruction the same way. Pointers are opaque, so
+    /// pointer conversions are no-ops; integers and pointers convert with
+    /// `inttoptr` and `ptrtoint`. Qualifiers play no part.
+    fn convert(&self, val: BasicValueEnum<'ctx>, from: &Type, to: &Type) -> BasicValueEnum<'ctx> {
+        let (from, to) = (from.unqualified(), to.unqualified());
+        if from == to || *to == Type::Void || (from.is_pointer() && to.is_pointer()) {
+            return val;
+        }
+        if to.is_pointer() {
+            let ptr_type = self.context.ptr_type(AddressSpace::default());
+            return self.builder.build_int_to_ptr(val.into_int_value(), ptr_type, "inttoptr").expect("inttoptr").into();
+        }
+        let target = self.llvm_type(to);
+        if from.is_pointer() {
+            return self.builder.build_ptr_to_int(val.into_pointer_value(), target.into_int_type(), "ptrtoint").expect("ptrtoint").into();
+        }
+        match (from.is_floating(), to.is_floating()) {
+            (true, true) => self.builder.build_float_cast(val.into_float_value(), target.into_float_type(), "convtmp").expect("fp cast").into(),
+            (false, true) if from.is_signed() => {
+                self.builder.build_signed_int_to_float(val.into_int_value(), target.into_float_type(), "convtmp").expect("sitofp").into()
+            }
+            (false, true) => {
+                self.builder.build_unsigned_int_to_float(val.into_int_value(), target.into_float_type(), "convtmp").expect("uitofp").into()
+            }
+            (true, false) if to.is_signed() => {
+                self.builder.build_float_to_signed_int(val.into_float_value(), target.into_int_type(), "convtmp").expect("fptosi").into()
+            }
+            (true, false) => {
+                self.builder.build_float_to_unsigned_int(val.into_float_value(), target.into_int_type(), "convtmp").expect("fptoui").into()
+            }
+            (false, false) => {
+                let target = target.into_int_type();
+                self.builder.build_int_cast_sign_flag(val.into_int_value(), target, from.is_signed(), "convtmp").expect("int cast").into()
+            }
+        }
+    }
+
+    /// Emits a string literal as a private constant array and returns a pointer to
+    /// its first character. Identical literals share one global.
+    fn gen_string(&mut self, value: &[u8]) -> PointerValue<'ctx> {
+        if let Some(ptr) = self.strings.get(value) {
+            return *ptr;
+        }
+        let init = self.context.const_string(value, true);
+        let global = self.module.add_global(init.get_type(), None, ".str");
+        global.set_linkage(Linkage::Private);
+        global.set_unnamed_addr(true);
+        global.set_constant(true);
+        global.set_alignment(1);
+        global.set_initializer(&init);
+        let ptr = global.as_pointer_value();
+        self.strings.insert(value.to_vec(), ptr);
+        ptr
+    }
+
+    /// Builds the constant initial value of a global of type `ty`. Whatever the
+    /// initializer leaves out is zero.
+    ///
+    /// A union constant holds its first member rather than the member its LLVM
+    /// type is built from, so unions, and aggregates containing them, get a
+    /// packed constant of their own type instead (see `const_packed`). The
+    /// constant for a given C type always has the same LLVM type either way.
+    fn const_initializer(&mut self, ty: &Type, init: Option<&Expr>) -> BasicValueEnum<'ctx> {
+        let items: &[Expr] = match init {
+            Some(Expr::InitList { items, .. }) => items,
+            _ => &[],
+        };
+        match (ty.unqualified(), init) {
+            (Type::Array(elem, len), None | Some(Expr::InitList { .. })) => {
+                let values: Vec<BasicValueEnum> = (0..*len).map(|i| self.const_initializer(elem, items.get(i))).collect();
+                let elem_type = values.first().map_or_else(|| self.llvm_type(elem), |value| value.get_type());
+                self.const_array(elem_type, &values).into()
+            }
+            (Type::Struct(tag), None | Some(Expr::InitList { .. })) => {
+                let members = self.structs[tag].clone();
+                let values: Vec<BasicValueEnum> = members.iter().enumerate().map(|(i, (_, ty))| self.const_initializer(ty, items.get(i))).collect();
+                let struct_type = self.llvm_type(ty).into_struct_type();
+                if values.iter().zip(struct_type.get_field_types()).all(|(value, field)| value.get_type() == field) {
+                    return struct_type.const_named_struct(&values).into();
+                }
+                let fields = values.into_iter().enumerate().map(|(i, value)| (self.target_data.offset_of_element(&struct_type, i as u32).unwrap(), value)).collect();
+                self.const_packed(fields, self.target_data.get_abi_size(&struct_type))
+            }
+            (Type::Union(tag), None | Some(Expr::InitList { .. })) => {
+                let first = self.structs[tag].first().map(|(_, ty)| ty.clone());
+                let fields = first.map(|ty| (0, self.const_initializer(&ty, items.first()))).into_iter().collect();
+                self.const_packed(fields, self.target_data.get_abi_size(&self.llvm_type(ty)))
+            }
+            (_, None) => self.llvm_type(ty).const_zero(),
+            (Type::Array(_, len), Some(Expr::StringLiteral { value, .. })) => self.const_chars(value, *len).into(),
+            (_, Some(Expr::StringLiteral { value, .. })) => self.gen_string(value).into(),
+            (Type::Pointer(_), Some(init)) => self.const_pointer(init).into(),
+            (_, Some(init)) if ty.is_floating() => {
+                let value = eval_const_float(init, &mut |e| self.sizeof_value(e)).expect("global initializer is not constant");
+                self.llvm_type(ty).into_float_type().const_float(value).into()
+            }
+            (_, Some(init)) => {
+                // A floating initializer is truncated toward zero, like any conversion to an integer.
+                let value = eval_const(init, &mut |e| self.sizeof_value(e))
+                    .or_else(|| eval_const_float(init, &mut |e| self.sizeof_value(e)).map(|v| v as i64))
+                    .expect("global initializer is not constant");
+                self.llvm_type(ty).into_int_type().const_int(value as u64, true).into()
+            }
+        }
+    }
+
+    /// The value of the address constant `expr`. This is either an integer
+    /// constant converted to a pointer, such as `(int *)16`, with 0 the null
+    /// pointer, or the address of a static object or function, possibly offset
+    /// by an integer constant, as in `&x`, `arr + 1` or `"abc" + 1`.
+    fn const_pointer(&mut self, expr: &Expr) -> PointerValue<'ctx> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        match eval_const(expr, &mut |e| self.sizeof_value(e)) {
+            Some(0) => return ptr_type.const_null(),
+            Some(address) => return self.context.i64_type().const_int(address as u64, false).const_to_pointer(ptr_type),
+            None => {}
+        }
+        match expr {
+            Expr::Unary { op: TokenKind::Ampersand, operand, .. } => self.const_lvalue(operand),
+            Expr::Cast { expr, .. } => self.const_pointer(expr),
+            Expr::Binary { op: op @ (TokenKind::Plus | TokenKind::Minus), left, right, .. } => self.const_offset(left, right, *op == TokenKind::Minus),
+            // An array or function decays to its address.
+            _ => self.const_lvalue(expr),
+        }
+    }
+
+    /// The constant address of the static object (or function) designated by `expr`.
+    fn const_lvalue(&mut self, expr: &Expr) -> PointerValue<'ctx> {
+        match expr {
+            Expr::Var { name, .. } => self.lookup_variable(name).0,
+            Expr::StringLiteral { value, .. } => self.gen_string(value),
+            Expr::Index { base, index, .. } => self.const_offset(base, index, false),
+            Expr::Unary { op: TokenKind::Star, operand, .. } => self.const_pointer(operand),
+            Expr::Member { base, member, arrow, .. } => {
+                let (ptr, struct_ty) = if *arrow {
+                    (self.const_pointer(base), self.expr_type(base).pointee().unwrap().clone())
+                } else {
+                    (self.const_lvalue(base), self.object_type(base))
+                };
+                let (index, _) = self.struct_member(&struct_ty, member);
+                if let Type::Union(_) = struct_ty {
+                    return ptr;
+                }
+                let indices = [self.i32_type.const_zero(), self.i32_type.const_int(u64::from(index), false)];
+                // SAFETY: the member lies inside the struct.
+                unsafe { ptr.const_in_bounds_gep(self.llvm_type(&struct_ty), &indices) }
+            }
+            _ => unreachable!("expression is not a static lvalue"),
+        }
+    }
+
+    /// The constant address `left + right` (or `left - right` when `negate` is
+    /// set), where one operand is an address constant and the other an integer
+    /// constant counting elements.
+    fn const_offset(&mut self, left: &Expr, right: &Expr, negate: bool) -> PointerValue<'ctx> {
+        let (ptr_expr, offset_expr) = if self.expr_type(left).is_pointer() { (left, right) } else { (right, left) };
+        let pointee = self.expr_type(ptr_expr).pointee().unwrap().clone();
+        let ptr = self.const_pointer(ptr_expr);
+        let offset = eval_const(offset_expr, &mut |e| self.sizeof_value(e)).expect("offset is not constant");
+        let index = self.context.i64_type().const_int((if negate { -offset } else { offset }) as u64, true);
+        // SAFETY: as for `gen_ptr_offset`, an address outside the object is undefined in C.
+        unsafe { ptr.const_in_bounds_gep(self.llvm_type(&pointee), &[index]) }
+    }
+
+    /// A packed constant struct placing each of `fields` at its byte offset, with
+    /// zero bytes in the gaps and up to `size`.
+    fn const_packed(&self, fields: Vec<(u64, BasicValueEnum<'ctx>)>, size: u64) -> BasicValueEnum<'ctx> {
+        let zeros = |len: u64| -> BasicValueEnum<'ctx> { self.context.i8_type().array_type(len as u32).const_zero().into() };
+        let mut values = Vec::new();
+        let mut end = 0;
+        for (offset, value) in fields {
+            if offset > end {
+                values.push(zeros(offset - end));
             }
+            end = offset + self.target_data.get_abi_size(&value.get_type());
+            values.push(value);
+        }
+        if size > end {
+            values.push(zeros(size - end));
+        }
+        self.context.const_struct(&values, true).into()
+    }
+
+    fn const_array(&self, elem_ty: BasicTypeEnum<'ctx>, values: &[BasicValueEnum<'ctx>]) -> ArrayValue<'ctx> {
+        match elem_ty {
+            BasicTypeEnum::IntType(ty) => ty.const_array(&values.iter().map(|v| v.into_int_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::FloatType(ty) => ty.const_array(&values.iter().map(|v| v.into_float_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::PointerType(ty) => ty.const_array(&values.iter().map(|v| v.into_pointer_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::ArrayType(ty) => ty.const_array(&values.iter().map(|v| v.into_array_value()).collect::<Vec<_>>()),
+            BasicTypeEnum::StructType(ty) => ty.const_array(&values.iter().map(|v| v.into_struct_value()).collect::<Vec<_>>()),
+            other => unreachable!("no arrays of {:?}", other),
         }
     }
 
+    /// The contents of a `char[len]` initialized from a string literal, padded with NULs.
+    fn const_chars(&self, value: &[u8], len: usize) -> ArrayValue<'ctx> {
+        let mut bytes = value.to_vec();
+        bytes.resize(len, 0);
+        self.context.const_string(&bytes, false)
+    }
+
+    /// Stores the initializer of a local of type `ty` into `ptr`. Arrays are zeroed
+    /// first, so elements the initializer leaves out are zero.
+    fn gen_initializer(&mut self, ptr: PointerValue<'ctx>, ty: &Type, init: &Expr) {
+        match (ty.unqualified(), init) {
+            (Type::Array(elem, _), Expr::InitList { items, .. }) => {
+                self.builder.build_store(ptr, self.llvm_type(ty).const_zero()).expect("store failed");
+                for (i, item) in items.iter().enumerate() {
+                    let index = self.context.i64_type().const_int(i as u64, false);
+                    let elem_ptr = self.gen_array_element(ptr, ty, index, "arrayinit");
+                    self.gen_initializer(elem_ptr, elem, item);
+                }
+            }
+            (Type::Struct(tag), Expr::InitList { items, .. }) => {
+                let struct_type = self.llvm_type(ty);
+                self.builder.build_store(ptr, struct_type.const_zero()).expect("store failed");
+                let members = self.structs[tag].clone();
+                for (i, (item, (name, member_ty))) in items.iter().zip(&members).enumerate() {
+                    let member_ptr = self.builder.build_struct_gep(struct_type, ptr, i as u32, name).expect("struct gep");
+                    self.gen_initializer(member_ptr, member_ty, item);
+                }
+            }
+            // Only the first member of a union can be initialized, and it lies at
+            // the union's own address.
+            (Type::Union(tag), Expr::InitList { items, .. }) => {
+                self.builder.build_store(ptr, self.llvm_type(ty).const_zero()).expect("store failed");
+                let first = self.structs[tag].first().map(|(_, ty)| ty.clone());
+                if let (Some(item), Some(member_ty)) = (items.first(), first) {
+                    self.gen_initializer(ptr, &member_ty, item);
+                }
+            }
+            (Type::Array(_, len), Expr::StringLiteral { value, .. }) => {
+                self.builder.build_store(ptr, self.const_chars(value, *len)).expect("store failed");
+            }
+            _ => {
+                let val = self.gen_expr_as(init, ty);
+                self.gen_store(ptr, ty, val);
+            }
+        }
+    }
+
+    /// Loads the value of the object at `ptr`. An array is not loaded but decays
+    /// to the address of its first element, and a function to its own address.
+    /// A `volatile` object is always read from memory, however many times the
+    /// program reads it.
+    fn gen_load(&self, ptr: PointerValue<'ctx>, ty: &Type, name: &str) -> BasicValueEnum<'ctx> {
+        if ty.is_array() || matches!(ty, Type::Function { .. }) {
+            return ptr.into();
+        }
+        let val = self.builder.build_load(self.llvm_type(ty), ptr, name).expect("load");
+        if ty.qualifiers().is_volatile {
+            val.as_instruction_value().unwrap().set_volatile(true).expect("volatile load");
+        }
+        val
+    }
+
+    /// Stores `val` into the object of type `ty` at `ptr`, never eliding or
+    /// merging the store if the object is `volatile`.
+    fn gen_store(&self, ptr: PointerValue<'ctx>, ty: &Type, val: BasicValueEnum<'ctx>) {
+        let store = self.builder.build_store(ptr, val).expect("store failed");
+        if ty.qualifiers().is_volatile {
+            store.set_volatile(true).expect("volatile store");
+        }
+    }
+
+    /// Generates `expr` and converts the result to `ty`.
+    fn gen_expr_as(&mut self, expr: &Expr, ty: &Type) -> BasicValueEnum<'ctx> {
+        let from = self.expr_type(expr);
+        let val = self.gen_expr(expr);
+        self.convert(val, &from, ty)
+    }
+
     fn gen_stmt(&mut self, stmt: &Stmt) {
+        // Code following a `return`, `break` or `continue` is unreachable but must
+        // still be emitted into a block of its own.
+        if self.builder.get_insert_block().unwrap().get_terminator().is_some() {
+            let dead_bb = self.context.append_basic_block(self.function.unwrap(), "dead");
+            self.builder.position_at_end(dead_bb);
+        }
         match stmt {
             Stmt::Empty => {}
-            Stmt::LocalVar { name, init, .. } => {
-                let ptr = self.builder.build_alloca(self.i32_type, name).expect("alloca failed");
-                let val = self.gen_expr(init);
-                self.builder.build_store(ptr, val).expect("store failed");
-                self.variables.insert(name.clone(), ptr);
+            // A static local is a global only visible in its block, named after
+            // the function as in `counter.calls`.
+            Stmt::LocalVar { name, ty, init, storage: Some(StorageClass::Static), .. } => {
+                let fn_name = self.function.unwrap().get_name().to_str().unwrap().to_string();
+                let global = self.module.add_global(self.llvm_type(ty), None, &format!("{}.{}", fn_name, name));
+                global.set_linkage(Linkage::Internal);
+                let global = self.define_global(global, ty, init.as_ref());
+                self.declare_local(name, global.as_pointer_value(), ty);
             }
-            Stmt::Expr(expr) => {
-                if let Expr::Binary { op: TokenKind::Assign, left, right, .. } = expr.as_ref() {
-                    if let Expr::Var { name, .. } = &**left {
-                        let val = self.gen_expr(right);
-                        let ptr = *self.variables.get(name).expect("undefined variable");
-                        self.builder.build_store(ptr, val).expect("store failed");
-                    }
-                } else {
-                    self.gen_expr(expr);
+            Stmt::LocalVar { name, ty, storage: Some(StorageClass::Extern), .. } => {
+                let global = self.module.get_global(name).unwrap_or_else(|| self.module.add_global(self.llvm_type(ty), None, name));
+                self.declare_local(name, global.as_pointer_value(), ty);
+            }
+            Stmt::LocalVar { name, ty, init, .. } => {
+                let ptr = self.create_entry_alloca(name, ty);
+                if let Some(init) = init {
+                    self.gen_initializer(ptr, ty, init);
                 }
+                self.declare_local(name, ptr, ty);
             }
-            Stmt::Return(expr) => {
-                let val = self.gen_expr(expr);
+            Stmt::Expr(expr) => {
+                self.gen_expr(expr);
+            }
+            Stmt::Return(Some(expr), _) => {
+                let ret_ty = self.ret_ty.clone();
+                let val = self.gen_expr_as(expr, &ret_ty);
                 let _ = self.builder.build_return(Some(&val));
             }
+            Stmt::Return(None, _) => {
+                let _ = self.builder.build_return(None);
+            }
+            Stmt::Break(_) => {
+                let break_bb = *self.break_targets.last().expect("break outside of loop or switch");
+                let _ = self.builder.build_unconditional_branch(break_bb);
+            }
+            Stmt::Continue(_) => {
+                let continue_bb = *self.continue_targets.last().expect("continue outside of loop");
+                let _ = self.builder.build_unconditional_branch(continue_bb);
+            }
             Stmt::If { cond, then_block, else_block } => {
                 self.gen_if(cond, then_block, else_block);
             }
             Stmt::While(cond, body) => {
                 self.gen_while(cond, body);
             }
+            Stmt::DoWhile(body, cond) => {
+                self.gen_do_while(body, cond);
+            }
+            Stmt::Switch { cond, body } => {
+                self.gen_switch(cond, body);
+            }
+            Stmt::Case(..) | Stmt::Default(_) => unreachable!("case label outside of switch body"),
             Stmt::For { init, cond, inc, body } => {
                 self.gen_for(init, cond, inc, body);
             }
             Stmt::Block(stmts) => {
+                self.enter_scope();
                 for s in stmts {
                     self.gen_stmt(s);
                 }
+                self.exit_scope();
             }
         }
     }
 
     fn gen_if(&mut self, cond: &Expr, then_block: &[Stmt], else_block: &Option<Vec<Stmt>>) {
+        let cond_bool = self.gen_cond(cond);
+
         let func = self.function.unwrap();
         let then_bb = self.context.append_basic_block(func, "then");
         let else_bb = self.context.append_basic_block(func, "else");
         let cont_bb = self.context.append_basic_block(func, "cont");
 
-        let cond_val = self.gen_expr(cond).into_int_value();
-        let cond_bool = if cond_val.get_type().get_bit_width() == 1 {
-            cond_val
-        } else {
-            let zero = self.i32_type.const_zero();
-            self.builder.build_int_compare(inkwell::IntPredicate::NE, cond_val, zero, "ifcond").expect("icmp")
-        };
-
         let _ = self.builder.build_conditional_branch(cond_bool, then_bb, else_bb);
 
         self.builder.position_at_end(then_bb);
+        self.enter_scope();
         for s in then_block { self.gen_stmt(s); }
+        self.exit_scope();
         if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
             let _ = self.builder.build_unconditional_branch(cont_bb);
         }
 
         self.builder.position_at_end(else_bb);
-        if let Some(els) = else_block { for s in els { self.gen_stmt(s); } }
+        if let Some(els) = else_block {
+            self.enter_scope();
+            for s in els { self.gen_stmt(s); }
+            self.exit_scope();
+        }
         if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
             let _ = self.builder.build_unconditional_branch(cont_bb);
         }
@@ -171,19 +798,19 @@ impl<'ctx> LLVMCodeGen<'ctx> {
         let _ = self.builder.build_unconditional_branch(loop_bb);
         self.builder.position_at_end(loop_bb);
 
-        let cond_val = self.gen_expr(cond).into_int_value();
-        let cond_bool = if cond_val.get_type().get_bit_width() == 1 {
-            cond_val
-        } else {
-            let zero = self.i32_type.const_zero();
-            self.builder.build_int_compare(inkwell::IntPredicate::SLT, cond_val, zero, "whilecond").expect("icmp")
-        };
+        let cond_bool = self.gen_cond(cond);
 
         let body_bb = self.context.append_basic_block(func, "body");
         let _ = self.builder.build_conditional_branch(cond_bool, body_bb, cont_bb);
 
         self.builder.position_at_end(body_bb);
+        self.continue_targets.push(loop_bb);
+        self.break_targets.push(cont_bb);
+        self.enter_scope();
         for s in body { self.gen_stmt(s); }
+        self.exit_scope();
+        self.continue_targets.pop();
+        self.break_targets.pop();
         if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
             let _ = self.builder.build_unconditional_branch(loop_bb);
         }
@@ -191,55 +818,531 @@ impl<'ctx> LLVMCodeGen<'ctx> {
         self.builder.position_at_end(cont_bb);
     }
 
-    fn gen_for(&mut self, init: &Option<Box<Expr>>, cond: &Option<Box<Expr>>, inc: &Option<Box<Expr>>, body: &[Stmt]) {
-        if let Some(i) = init { let _ = self.gen_expr(i); }
+    fn gen_do_while(&mut self, body: &[Stmt], cond: &Expr) {
+        let func = self.function.unwrap();
+        let body_bb = self.context.append_basic_block(func, "body");
+        let cond_bb = self.context.append_basic_block(func, "loop");
+        let cont_bb = self.context.append_basic_block(func, "cont");
+
+        let _ = self.builder.build_unconditional_branch(body_bb);
+
+        self.builder.position_at_end(body_bb);
+        self.continue_targets.push(cond_bb);
+        self.break_targets.push(cont_bb);
+        self.enter_scope();
+        for s in body { self.gen_stmt(s); }
+        self.exit_scope();
+        self.continue_targets.pop();
+        self.break_targets.pop();
+        if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
+            let _ = self.builder.build_unconditional_branch(cond_bb);
+        }
+
+        self.builder.position_at_end(cond_bb);
+        let cond_bool = self.gen_cond(cond);
+        let _ = self.builder.build_conditional_branch(cond_bool, body_bb, cont_bb);
+
+        self.builder.position_at_end(cont_bb);
+    }
+
+    /// Lowers a `switch` to LLVM's `switch` instruction. Every label in the body gets
+    /// its own block; falling off the end of one label's statements branches into the next.
+    fn gen_switch(&mut self, cond: &Expr, body: &[Stmt]) {
+        let func = self.function.unwrap();
+        let cond_ty = self.expr_type(cond).promote();
+        let val = self.gen_expr_as(cond, &cond_ty).into_int_value();
+        let case_type = val.get_type();
+
+        let mut cases = Vec::new();
+        let mut default_bb = None;
+        let mut label_blocks = Vec::new();
+        for s in body {
+            match s {
+                Stmt::Case(value, _) => {
+                    let bb = self.context.append_basic_block(func, "case");
+                    let v = eval_const(value, &mut |e| self.sizeof_value(e)).expect("case label is not constant");
+                    cases.push((case_type.const_int(wrap(v, &cond_ty) as u64, true), bb));
+                    label_blocks.push(bb);
+                }
+                Stmt::Default(_) => {
+                    let bb = self.context.append_basic_block(func, "default");
+                    default_bb = Some(bb);
+                    label_blocks.push(bb);
+                }
+                _ => {}
+            }
+        }
+        let end_bb = self.context.append_basic_block(func, "sw.end");
+
+        let _ = self.builder.build_switch(val, default_bb.unwrap_or(end_bb), &cases);
+
+        self.break_targets.push(end_bb);
+        self.enter_scope();
+        let mut labels = label_blocks.into_iter();
+        for s in body {
+            if let Stmt::Case(..) | Stmt::Default(_) = s {
+                let bb = labels.next().unwrap();
+                if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
+                    let _ = self.builder.build_unconditional_branch(bb);
+                }
+                self.builder.position_at_end(bb);
+            } else {
+                self.gen_stmt(s);
+            }
+        }
+        self.exit_scope();
+        self.break_targets.pop();
+        if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
+            let _ = self.builder.build_unconditional_branch(end_bb);
+        }
+
+        self.builder.position_at_end(end_bb);
+    }
+
+    fn gen_for(&mut self, init: &[Stmt], cond: &Option<Box<Expr>>, inc: &Option<Box<Expr>>, body: &[Stmt]) {
+        self.enter_scope();
+        for s in init { self.gen_stmt(s); }
         let func = self.function.unwrap();
         let loop_bb = self.context.append_basic_block(func, "loop");
+        let body_bb = self.context.append_basic_block(func, "body");
+        let inc_bb = self.context.append_basic_block(func, "inc");
         let cont_bb = self.context.append_basic_block(func, "cont");
 
         let _ = self.builder.build_unconditional_branch(loop_bb);
         self.builder.position_at_end(loop_bb);
 
         if let Some(c) = cond {
-            let cond_val = self.gen_expr(c).into_int_value();
-            let zero = self.i32_type.const_zero();
-            let cond_bool = self.builder.build_int_compare(inkwell::IntPredicate::SLT, cond_val, zero, "forcond").expect("icmp");
-            let _ = self.builder.build_conditional_branch(cond_bool, loop_bb, cont_bb);
+            let cond_bool = self.gen_cond(c);
+            let _ = self.builder.build_conditional_branch(cond_bool, body_bb, cont_bb);
+        } else {
+            let _ = self.builder.build_unconditional_branch(body_bb);
         }
 
+        self.builder.position_at_end(body_bb);
+        self.continue_targets.push(inc_bb);
+        self.break_targets.push(cont_bb);
+        self.enter_scope();
         for s in body { self.gen_stmt(s); }
-        if let Some(i) = inc { let _ = self.gen_expr(i); }
+        self.exit_scope();
+        self.continue_targets.pop();
+        self.break_targets.pop();
         if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
-            let _ = self.builder.build_unconditional_branch(loop_bb);
+            let _ = self.builder.build_unconditional_branch(inc_bb);
         }
 
+        self.builder.position_at_end(inc_bb);
+        if let Some(i) = inc { let _ = self.gen_expr(i); }
+        let _ = self.builder.build_unconditional_branch(loop_bb);
+
         self.builder.position_at_end(cont_bb);
+        self.exit_scope();
+    }
+
+    fn is_boolean_op(op: &TokenKind) -> bool {
+        matches!(
+            op,
+            TokenKind::Less
+                | TokenKind::LessEqual
+                | TokenKind::Greater
+                | TokenKind::GreaterEqual
+                | TokenKind::Equal
+                | TokenKind::NotEqual
+                | TokenKind::LogicalAnd
+                | TokenKind::LogicalOr
+        )
+    }
+
+    /// Evaluates `expr` as a branch condition, producing an `i1`.
+    /// Comparisons and logical operators are emitted directly as `i1` instead of
+    /// being widened to `int` and compared against zero again.
+    fn gen_cond(&mut self, expr: &Expr) -> IntValue<'ctx> {
+        match expr {
+            Expr::Unary { op: TokenKind::LogicalNot, operand, .. } => {
+                let val = self.gen_cond(operand);
+                self.builder.build_not(val, "nottmp").expect("not")
+            }
+            Expr::Binary { op: TokenKind::LogicalAnd, left, right, .. } => self.gen_short_circuit(left, right, true),
+            Expr::Binary { op: TokenKind::LogicalOr, left, right, .. } => self.gen_short_circuit(left, right, false),
+            Expr::Binary { op, left, right, .. } if Self::is_boolean_op(op) => {
+                let ty = Type::common(&self.expr_type(left), &self.expr_type(right));
+                let lhs = self.gen_expr_as(left, &ty);
+                let rhs = self.gen_expr_as(right, &ty);
+                if ty.is_floating() {
+                    // Ordered comparisons are false for NaN operands, and `!=` is true.
+                    let (pred, name) = match op {
+                        TokenKind::Less => (FloatPredicate::OLT, "lttmp"),
+                        TokenKind::LessEqual => (FloatPredicate::OLE, "letmp"),
+                        TokenKind::Greater => (FloatPredicate::OGT, "gttmp"),
+                        TokenKind::GreaterEqual => (FloatPredicate::OGE, "getmp"),
+                        TokenKind::Equal => (FloatPredicate::OEQ, "eqtmp"),
+                        TokenKind::NotEqual => (FloatPredicate::UNE, "netmp"),
+                        _ => unreachable!(),
+                    };
+                    return self.builder.build_float_compare(pred, lhs.into_float_value(), rhs.into_float_value(), name).expect("fcmp");
+                }
+                // Addresses are compared as unsigned values.
+                let (pred, name) = match (op, ty.is_pointer() || ty.is_unsigned()) {
+                    (TokenKind::Less, false) => (IntPredicate::SLT, "lttmp"),
+                    (TokenKind::LessEqual, false) => (IntPredicate::SLE, "letmp"),
+                    (TokenKind::Greater, false) => (IntPredicate::SGT, "gttmp"),
+                    (TokenKind::GreaterEqual, false) => (IntPredicate::SGE, "getmp"),
+                    (TokenKind::Less, true) => (IntPredicate::ULT, "lttmp"),
+                    (TokenKind::LessEqual, true) => (IntPredicate::ULE, "letmp"),
+                    (TokenKind::Greater, true) => (IntPredicate::UGT, "gttmp"),
+                    (TokenKind::GreaterEqual, true) => (IntPredicate::UGE, "getmp"),
+                    (TokenKind::Equal, _) => (IntPredicate::EQ, "eqtmp"),
+                    (TokenKind::NotEqual, _) => (IntPredicate::NE, "netmp"),
+                    _ => unreachable!(),
+                };
+                if ty.is_pointer() {
+                    let (lhs, rhs) = (lhs.into_pointer_value(), rhs.into_pointer_value());
+                    self.builder.build_int_compare(pred, lhs, rhs, name).expect("icmp")
+                } else {
+                    self.builder.build_int_compare(pred, lhs.into_int_value(), rhs.into_int_value(), name).expect("icmp")
+                }
+            }
+            _ => {
+                let val = self.gen_expr(expr);
+                if val.is_pointer_value() {
+                    return self.builder.build_is_not_null(val.into_pointer_value(), "tobool").expect("icmp");
+                }
+                if val.is_float_value() {
+                    let val = val.into_float_value();
+                    let zero = val.get_type().const_zero();
+                    return self.builder.build_float_compare(FloatPredicate::UNE, val, zero, "tobool").expect("fcmp");
+                }
+                let val = val.into_int_value();
+                let zero = val.get_type().const_zero();
+                self.builder.build_int_compare(IntPredicate::NE, val, zero, "tobool").expect("icmp")
+            }
+        }
+    }
+
+    /// Lowers `&&` (`is_and`) or `||` so that `right` is only evaluated when `left`
+    /// does not already decide the result.
+    fn gen_short_circuit(&mut self, left: &Expr, right: &Expr, is_and: bool) -> IntValue<'ctx> {
+        let func = self.function.unwrap();
+        let lhs = self.gen_cond(left);
+        let lhs_bb = self.builder.get_insert_block().unwrap();
+        let rhs_bb = self.context.append_basic_block(func, if is_and { "and.rhs" } else { "or.rhs" });
+        let merge_bb = self.context.append_basic_block(func, if is_and { "and.end" } else { "or.end" });
+
+        if is_and {
+            let _ = self.builder.build_conditional_branch(lhs, rhs_bb, merge_bb);
+        } else {
+            let _ = self.builder.build_conditional_branch(lhs, merge_bb, rhs_bb);
+        }
+
+        self.builder.position_at_end(rhs_bb);
+        let rhs = self.gen_cond(right);
+        let rhs_end_bb = self.builder.get_insert_block().unwrap();
+        let _ = self.builder.build_unconditional_branch(merge_bb);
+
+        self.builder.position_at_end(merge_bb);
+        let bool_type = self.context.bool_type();
+        let short_val = bool_type.const_int(if is_and { 0 } else { 1 }, false);
+        let phi = self.builder.build_phi(bool_type, if is_and { "andtmp" } else { "ortmp" }).expect("phi");
+        phi.add_incoming(&[(&short_val, lhs_bb), (&rhs, rhs_end_bb)]);
+        phi.as_basic_value().into_int_value()
+    }
+
+    /// Lowers `cond ? a : b`, evaluating only the selected arm and converting it to `ty`.
+    fn gen_conditional(&mut self, cond: &Expr, then_expr: &Expr, else_expr: &Expr, ty: &Type) -> BasicValueEnum<'ctx> {
+        let func = self.function.unwrap();
+        let cond_bool = self.gen_cond(cond);
+        let then_bb = self.context.append_basic_block(func, "cond.true");
+        let else_bb = self.context.append_basic_block(func, "cond.false");
+        let merge_bb = self.context.append_basic_block(func, "cond.end");
+        let _ = self.builder.build_conditional_branch(cond_bool, then_bb, else_bb);
+
+        self.builder.position_at_end(then_bb);
+        let then_val = self.gen_expr_as(then_expr, ty);
+        let then_end_bb = self.builder.get_insert_block().unwrap();
+        let _ = self.builder.build_unconditional_branch(merge_bb);
+
+        self.builder.position_at_end(else_bb);
+        let else_val = self.gen_expr_as(else_expr, ty);
+        let else_end_bb = self.builder.get_insert_block().unwrap();
+        let _ = self.builder.build_unconditional_branch(merge_bb);
+
+        self.builder.position_at_end(merge_bb);
+        let phi = self.builder.build_phi(then_val.get_type(), "condtmp").expect("phi");
+        phi.add_incoming(&[(&then_val, then_end_bb), (&else_val, else_end_bb)]);
+        phi.as_basic_value()
+    }
+
+    fn gen_bool_as_int(&mut self, expr: &Expr) -> BasicValueEnum<'ctx> {
+        let val = self.gen_cond(expr);
+        self.builder.build_int_z_extend(val, self.i32_type, "booltmp").expect("zext").into()
+    }
+
+    /// Computes the address and type of an assignable expression.
+    fn gen_lvalue(&mut self, expr: &Expr) -> (PointerValue<'ctx>, Type) {
+        match expr {
+            Expr::Var { name, .. } => self.lookup_variable(name),
+            Expr::StringLiteral { value, .. } => (self.gen_string(value), self.object_type(expr)),
+            Expr::Unary { op: TokenKind::Star, operand, .. } => {
+                let ty = self.object_type(expr);
+                (self.gen_expr(operand).into_pointer_value(), ty)
+            }
+            Expr::Index { base, index, .. } => {
+                let ty = self.object_type(expr);
+                let (ptr_expr, offset_expr) = if self.expr_type(base).is_pointer() { (base, index) } else { (index, base) };
+                // Subscripting an array addresses its element directly instead of
+                // going through the decayed pointer.
+                let array_ty = self.object_type(ptr_expr);
+                if array_ty.is_array() {
+                    let (array, _) = self.gen_lvalue(ptr_expr);
+                    let offset = self.gen_expr_as(offset_expr, &Type::Long).into_int_value();
+                    return (self.gen_array_element(array, &array_ty, offset, "arrayidx"), ty);
+                }
+                let ptr = self.gen_expr(ptr_expr).into_pointer_value();
+                let offset = self.gen_expr_as(offset_expr, &Type::Long).into_int_value();
+                (self.gen_ptr_offset(ptr, &ty, offset, false), ty)
+            }
+            Expr::Member { base, member, arrow, .. } => {
+                let (ptr, struct_ty) = if *arrow {
+                    let struct_ty = self.expr_type(base).pointee().unwrap().clone();
+                    (self.gen_expr(base).into_pointer_value(), struct_ty)
+                } else if matches!(**base, Expr::Var { .. } | Expr::Unary { op: TokenKind::Star, .. } | Expr::Index { .. } | Expr::Member { .. }) {
+                    self.gen_lvalue(base)
+                } else {
+                    // A struct returned by a call or assignment has no address of its
+                    // own, so it is spilled to a temporary first.
+                    let struct_ty = self.object_type(base);
+                    let tmp = self.create_entry_alloca("tmp", &struct_ty);
+                    let val = self.gen_expr(base);
+                    self.builder.build_store(tmp, val).expect("store failed");
+                    (tmp, struct_ty)
+                };
+                let (index, ty) = self.struct_member(&struct_ty, member);
+                // Every member of a union starts at the union's address.
+                if let Type::Union(_) = struct_ty {
+                    return (ptr, ty);
+                }
+                let member_ptr = self.builder.build_struct_gep(self.llvm_type(&struct_ty), ptr, index, member).expect("struct gep");
+                (member_ptr, ty)
+            }
+            _ => unreachable!("expression is not an lvalue"),
+        }
+    }
+
+    /// Advances `ptr` by `offset` elements of `pointee` (or moves it back when
+    /// `negate` is set), as C scales pointer arithmetic by the element size.
+    /// `offset` is a `long`, already converted from the type of the operand.
+    fn gen_ptr_offset(&self, ptr: PointerValue<'ctx>, pointee: &Type, offset: IntValue<'ctx>, negate: bool) -> PointerValue<'ctx> {
+        let mut index = offset;
+        if negate {
+            index = self.builder.build_int_neg(index, "idxneg").expect("neg");
+        }
+        // SAFETY: C leaves pointer arithmetic outside the pointed-to object undefined,
+        // which is exactly what `inbounds` tells LLVM.
+        unsafe { self.builder.build_in_bounds_gep(self.llvm_type(pointee), ptr, &[index], "ptrtmp").expect("gep") }
+    }
+
+    /// Address of element `index` of the array of type `array_ty` at `array`.
+    fn gen_array_element(&self, array: PointerValue<'ctx>, array_ty: &Type, index: IntValue<'ctx>, name: &str) -> PointerValue<'ctx> {
+        let zero = self.context.i64_type().const_zero();
+        // SAFETY: as for `gen_ptr_offset`, indexing outside the array is undefined in C.
+        unsafe { self.builder.build_in_bounds_gep(self.llvm_type(array_ty), array, &[zero, index], name).expect("gep") }
+    }
+
+    /// Lowers `+` or `-` with a pointer operand: `p + n`, `n + p`, `p - n` or `p - q`.
+    fn gen_pointer_arith(&mut self, op: &TokenKind, left: &Expr, right: &Expr) -> BasicValueEnum<'ctx> {
+        let (lt, rt) = (self.expr_type(left), self.expr_type(right));
+        if lt.is_pointer() && rt.is_pointer() {
+            let pointee = self.llvm_type(lt.pointee().unwrap());
+            let lhs = self.gen_expr(left).into_pointer_value();
+            let rhs = self.gen_expr(right).into_pointer_value();
+            let diff = self.builder.build_ptr_diff(pointee, lhs, rhs, "subptr").expect("ptrdiff");
+            return self.builder.build_int_cast_sign_flag(diff, self.llvm_type(&Type::Long).into_int_type(), true, "subptr").expect("int cast").into();
+        }
+        let (ptr_expr, ptr_ty, offset_expr) = if lt.is_pointer() { (left, &lt, right) } else { (right, &rt, left) };
+        let ptr = self.gen_expr(ptr_expr).into_pointer_value();
+        let offset = self.gen_expr_as(offset_expr, &Type::Long).into_int_value();
+        self.gen_ptr_offset(ptr, ptr_ty.pointee().unwrap(), offset, *op == TokenKind::Minus).into()
+    }
+
+    /// Applies an arithmetic or bitwise operator to operands already converted to
+    /// `ty`, whose signedness selects the division and right shift instructions.
+    fn gen_arith(&self, op: &TokenKind, ty: &Type, lhs: BasicValueEnum<'ctx>, rhs: BasicValueEnum<'ctx>) -> BasicValueEnum<'ctx> {
+        if ty.is_floating() {
+            let (lhs, rhs) = (lhs.into_float_value(), rhs.into_float_value());
+            let result = match op {
+                TokenKind::Plus => self.builder.build_float_add(lhs, rhs, "addtmp").expect("fadd"),
+                TokenKind::Minus => self.builder.build_float_sub(lhs, rhs, "subtmp").expect("fsub"),
+                TokenKind::Star => self.builder.build_float_mul(lhs, rhs, "multmp").expect("fmul"),
+                TokenKind::Slash => self.builder.build_float_div(lhs, rhs, "divtmp").expect("fdiv"),
+                _ => unreachable!(),
+            };
+            return result.into();
+        }
+        let (lhs, rhs) = (lhs.into_int_value(), rhs.into_int_value());
+        let result = match op {
+            TokenKind::Plus => self.builder.build_int_add(lhs, rhs, "addtmp").expect("add"),
+            TokenKind::Minus => self.builder.build_int_sub(lhs, rhs, "subtmp").expect("sub"),
+            TokenKind::Star => self.builder.build_int_mul(lhs, rhs, "multmp").expect("mul"),
+            TokenKind::Slash if ty.is_unsigned() => self.builder.build_int_unsigned_div(lhs, rhs, "divtmp").expect("udiv"),
+            TokenKind::Slash => self.builder.build_int_signed_div(lhs, rhs, "divtmp").expect("sdiv"),
+            TokenKind::Percent if ty.is_unsigned() => self.builder.build_int_unsigned_rem(lhs, rhs, "remtmp").expect("urem"),
+            TokenKind::Percent => self.builder.build_int_signed_rem(lhs, rhs, "remtmp").expect("srem"),
+            TokenKind::Ampersand => self.builder.build_and(lhs, rhs, "andtmp").expect("and"),
+            TokenKind::Pipe => self.builder.build_or(lhs, rhs, "ortmp").expect("or"),
+            TokenKind::Caret => self.builder.build_xor(lhs, rhs, "xortmp").expect("xor"),
+            TokenKind::ShiftLeft => self.builder.build_left_shift(lhs, rhs, "shltmp").expect("shl"),
+            TokenKind::ShiftRight => self.builder.build_right_shift(lhs, rhs, ty.is_signed(), "shrtmp").expect("shr"),
+            _ => unreachable!(),
+        };
+        result.into()
+    }
+
+    /// Applies `++` or `--` to `operand`, evaluating its address once.
+    /// Returns the value before and after the update.
+    fn gen_increment(&mut self, op: &TokenKind, operand: &Expr) -> (BasicValueEnum<'ctx>, BasicValueEnum<'ctx>) {
+        let (ptr, ty) = self.gen_lvalue(operand);
+        let old = self.gen_load(ptr, &ty, "oldval");
+        let new: BasicValueEnum = if let Some(pointee) = ty.pointee() {
+            let one = self.context.i64_type().const_int(1, false);
+            self.gen_ptr_offset(old.into_pointer_value(), pointee, one, *op == TokenKind::MinusMinus).into()
+        } else if ty.is_floating() {
+            let old = old.into_float_value();
+            let one = old.get_type().const_float(1.0);
+            if *op == TokenKind::PlusPlus {
+                self.builder.build_float_add(old, one, "inctmp").expect("fadd").into()
+            } else {
+                self.builder.build_float_sub(old, one, "dectmp").expect("fsub").into()
+            }
+        } else {
+            let old = old.into_int_value();
+            let one = old.get_type().const_int(1, false);
+            if *op == TokenKind::PlusPlus {
+                self.builder.build_int_add(old, one, "inctmp").expect("add").into()
+            } else {
+                self.builder.build_int_sub(old, one, "dectmp").expect("sub").into()
+            }
+        };
+        self.gen_store(ptr, &ty, new);
+        (old, new)
     }
 
     fn gen_expr(&mut self, expr: &Expr) -> BasicValueEnum<'ctx> {
         match expr {
-            Expr::IntLiteral { value, .. } => self.i32_type.const_int(*value as u64, false).into(),
+            Expr::IntLiteral { value, ty, .. } => self.llvm_type(ty).into_int_type().const_int(*value as u64, false).into(),
+            Expr::FloatLiteral { value, ty, .. } => self.llvm_type(ty).into_float_type().const_float(*value).into(),
+            Expr::Cast { ty, expr, .. } => self.gen_expr_as(expr, ty),
+            Expr::SizeofType { ty, .. } => self.size_of(ty).into(),
+            Expr::SizeofExpr { expr, .. } => {
+                let ty = self.object_type(expr);
+                self.size_of(&ty).into()
+            }
+            Expr::StringLiteral { value, .. } => self.gen_string(value).into(),
             Expr::Var { name, .. } => {
-                let ptr = *self.variables.get(name).unwrap();
-                self.builder.build_load(self.i32_type, ptr, name).expect("load").into()
+                let (ptr, ty) = self.lookup_variable(name);
+                self.gen_load(ptr, &ty, name)
             }
-            Expr::Binary { op, left, right, .. } => {
-                let lhs = self.gen_expr(left).into_int_value();
-                let rhs = self.gen_expr(right).into_int_value();
-                let instr = match op {
-                    TokenKind::Plus => self.builder.build_int_add(lhs, rhs, "addtmp").expect("add"),
-                    TokenKind::Minus => self.builder.build_int_sub(lhs, rhs, "subtmp").expect("sub"),
-                    TokenKind::Star => self.builder.build_int_mul(lhs, rhs, "multmp").expect("mul"),
-                    TokenKind::Slash => self.builder.build_int_signed_div(lhs, rhs, "divtmp").expect("div"),
-                    TokenKind::Less => self.builder.build_int_compare(inkwell::IntPredicate::SLT, lhs, rhs, "lttmp").expect("icmp"),
-                    TokenKind::LessEqual => self.builder.build_int_compare(inkwell::IntPredicate::SLE, lhs, rhs, "letmp").expect("icmp"),
-                    TokenKind::Greater => self.builder.build_int_compare(inkwell::IntPredicate::SGT, lhs, rhs, "gttmp").expect("icmp"),
-                    TokenKind::GreaterEqual => self.builder.build_int_compare(inkwell::IntPredicate::SGE, lhs, rhs, "getmp").expect("icmp"),
-                    TokenKind::Equal => self.builder.build_int_compare(inkwell::IntPredicate::EQ, lhs, rhs, "eqtmp").expect("icmp"),
-                    TokenKind::NotEqual => self.builder.build_int_compare(inkwell::IntPredicate::NE, lhs, rhs, "netmp").expect("icmp"),
+            Expr::Binary { op: TokenKind::Assign, left, right, .. } => {
+                let (ptr, ty) = self.gen_lvalue(left);
+                let val = self.gen_expr_as(right, &ty);
+                self.gen_store(ptr, &ty, val);
+                val
+            }
+            Expr::Binary { op, left, right, .. } if op.is_assignment() => {
+                // `a op= b` computes `a op b` in the common type, then converts back to the type of `a`.
+                let arith_op = op.compound_op().unwrap();
+                let (ptr, ty) = self.gen_lvalue(left);
+                if let Some(pointee) = ty.pointee() {
+                    let old = self.gen_load(ptr, &ty, "oldval").into_pointer_value();
+                    let offset = self.gen_expr_as(right, &Type::Long).into_int_value();
+                    let new = self.gen_ptr_offset(old, pointee, offset, arith_op == TokenKind::Minus);
+                    self.gen_store(ptr, &ty, new.into());
+                    return new.into();
+                }
+                let op_ty = Type::binary_result(&arith_op, ty.unqualified(), &self.expr_type(right));
+                let old = self.gen_load(ptr, &ty, "oldval");
+                let lhs = self.convert(old, &ty, &op_ty);
+                let rhs = self.gen_expr_as(right, &op_ty);
+                let result = self.gen_arith(&arith_op, &op_ty, lhs, rhs);
+                let new = self.convert(result, &op_ty, &ty);
+                self.gen_store(ptr, &ty, new);
+                new
+            }
+            Expr::Unary { op: op @ (TokenKind::PlusPlus | TokenKind::MinusMinus), operand, .. } => {
+                let (_, new) = self.gen_increment(op, operand);
+                new
+            }
+            Expr::Postfix { op, operand, .. } => {
+                let (old, _) = self.gen_increment(op, operand);
+                old
+            }
+            Expr::Unary { op: TokenKind::LogicalNot, .. } => self.gen_bool_as_int(expr),
+            Expr::Unary { op: TokenKind::Ampersand, operand, .. } => self.gen_lvalue(operand).0.into(),
+            Expr::Unary { op: TokenKind::Star, .. } => {
+                let (ptr, ty) = self.gen_lvalue(expr);
+                self.gen_load(ptr, &ty, "dereftmp")
+            }
+            Expr::Index { .. } => {
+                let (ptr, ty) = self.gen_lvalue(expr);
+                self.gen_load(ptr, &ty, "arrayidx")
+            }
+            Expr::Member { member, .. } => {
+                let (ptr, ty) = self.gen_lvalue(expr);
+                self.gen_load(ptr, &ty, member)
+            }
+            Expr::InitList { .. } => unreachable!("initializer list used as an expression"),
+            Expr::Unary { op, operand, .. } if self.expr_type(operand).is_floating() => {
+                let val = self.gen_expr(operand).into_float_value();
+                match op {
+                    TokenKind::Minus => self.builder.build_float_neg(val, "negtmp").expect("fneg").into(),
+                    TokenKind::Plus => val.into(),
                     _ => unreachable!(),
+                }
+            }
+            Expr::Unary { op, operand, .. } => {
+                let ty = self.expr_type(operand).promote();
+                let val = self.gen_expr_as(operand, &ty).into_int_value();
+                match op {
+                    TokenKind::Minus => self.builder.build_int_neg(val, "negtmp").expect("neg").into(),
+                    TokenKind::Tilde => self.builder.build_not(val, "nottmp").expect("not").into(),
+                    TokenKind::Plus => val.into(),
+                    _ => unreachable!(),
+                }
+            }
+            Expr::Binary { op, .. } if Self::is_boolean_op(op) => self.gen_bool_as_int(expr),
+            Expr::Binary { op: op @ (TokenKind::Plus | TokenKind::Minus), left, right, .. }
+                if self.expr_type(left).is_pointer() || self.expr_type(right).is_pointer() =>
+            {
+                self.gen_pointer_arith(op, left, right)
+            }
+            Expr::Binary { op, left, right, .. } => {
+                // Shift counts are converted to the type of the shifted value, as LLVM requires.
+                let ty = self.expr_type(expr);
+                let lhs = self.gen_expr_as(left, &ty);
+                let rhs = self.gen_expr_as(right, &ty);
+                self.gen_arith(op, &ty, lhs, rhs)
+            }
+            Expr::Conditional { cond, then_expr, else_expr, .. } => {
+                let ty = self.expr_type(expr);
+                self.gen_conditional(cond, then_expr, else_expr, &ty)
+            }
+            Expr::Call { callee, args, .. } => {
+                let fn_val = self.module.get_function(callee).expect("undefined function");
+                let param_tys = match &self.globals[callee] {
+                    Type::Function { params, .. } => params.clone(),
+                    _ => unreachable!("call to a non-function"),
                 };
-                instr.into()
+                // Arguments matching `...` only undergo the default argument promotions.
+                let arg_vals: Vec<BasicMetadataValueEnum> = args
+                    .iter()
+                    .enumerate()
+                    .map(|(i, a)| {
+                        let ty = param_tys.get(i).cloned().unwrap_or_else(|| self.expr_type(a).promote_argument());
+                        self.gen_expr_as(a, &ty).into()
+                    })
+                    .collect();
+                let name = if fn_val.get_type().get_return_type().is_some() { "calltmp" } else { "" };
+                let call = self.builder.build_call(fn_val, &arg_vals, name).expect("call");
+                // The analyzer only lets void calls appear as statements, so the placeholder is never used.
+                call.try_as_basic_value().left().unwrap_or_else(|| self.i32_type.const_zero().into())
             }
         }
     }
@@ -1,4 +1,5 @@
 pub mod error;
 pub mod llvm_gen;
+pub mod target;
 
 pub use error::CodegenError;
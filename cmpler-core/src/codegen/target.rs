@@ -0,0 +1,28 @@
+use inkwell::targets::{CodeModel, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple};
+use inkwell::OptimizationLevel;
+use crate::codegen::CodegenError;
+
+/// Creates a target machine for the host. Both the module's data layout and the
+/// object files it emits come from it, so aggregates are laid out as `cc` would.
+pub fn host_target_machine(opt_level: OptimizationLevel) -> Result<TargetMachine, CodegenError> {
+    target_machine(&TargetMachine::get_default_triple(), opt_level)
+}
+
+/// Creates a target machine for `triple`, which need not be the host's.
+pub fn target_machine(triple: &TargetTriple, opt_level: OptimizationLevel) -> Result<TargetMachine, CodegenError> {
+    Target::initialize_all(&InitializationConfig::default());
+    let target = Target::from_triple(triple)
+        .map_err(|e| CodegenError::LlvmInitError(format!("Failed to get target: {}", e)))?;
+    let cpu = "generic";
+    let features = "";
+    // `cc` links position-independent executables by default, so references to
+    // string constants and libc functions must be PIC as well.
+    target.create_target_machine(
+        triple,
+        cpu,
+        features,
+        opt_level,
+        RelocMode::PIC,
+        CodeModel::Default,
+    ).ok_or_else(|| CodegenError::LlvmInitError("Failed to create target machine".into()))
+}
@@ -5,24 +5,35 @@ use crate::error::CompilerError;
 use crate::lexer::lex;
 use crate::parser::Parser;
 use crate::semantic::SemanticAnalyzer;
+use crate::semantic::layout::TargetLayout;
 
 use inkwell::context::Context;
 use inkwell::OptimizationLevel;
 use crate::codegen::llvm_gen::LLVMCodeGen;
-use inkwell::targets::{InitializationConfig, RelocMode, CodeModel, FileType, Target, TargetMachine};
+use crate::codegen::target::host_target_machine;
+use inkwell::targets::{FileType, TargetMachine};
 
-#[instrument(level = "info", skip(source))]
+/// Parses and checks `source` for the host.
 pub fn compile(source: &str) -> Result<Program, CompilerError> {
+    let tm = host_target_machine(OptimizationLevel::None).map_err(|e| CompilerError::Codegen(e.to_string()))?;
+    compile_for_target(source, &tm)
+}
+
+/// Parses and checks `source` for the machine `tm` generates code for, whose
+/// data layout decides what `sizeof` folds to.
+#[instrument(level = "info", skip(source, tm))]
+pub fn compile_for_target(source: &str, tm: &TargetMachine) -> Result<Program, CompilerError> {
     info!("Starting compilation");
+    let layout = TargetLayout::new(&tm.get_target_data());
 
     let tokens = lex(source);
     info!(token_count = tokens.len(), "Lexing complete");
 
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(tokens, layout);
     let program = parser.parse_program()?;
     info!(decls = program.decls.len(), "Parsing complete");
 
-    let mut analyzer = SemanticAnalyzer::new();
+    let mut analyzer = SemanticAnalyzer::new(layout);
     analyzer.analyze(&program)?;
     info!("Semantic analysis complete");
 
@@ -33,9 +44,18 @@ pub fn compile_to_llvm_ir(
     source: &str,
     opt_level: OptimizationLevel,
 ) -> Result<String, CompilerError> {
-    let program = compile(source)?;
+    let tm = host_target_machine(opt_level).map_err(|e| CompilerError::Codegen(e.to_string()))?;
+    compile_to_llvm_ir_for_target(source, opt_level, &tm)
+}
+
+pub fn compile_to_llvm_ir_for_target(
+    source: &str,
+    opt_level: OptimizationLevel,
+    tm: &TargetMachine,
+) -> Result<String, CompilerError> {
+    let program = compile_for_target(source, tm)?;
     let context = Context::create();
-    let module = LLVMCodeGen::compile_program(&context, &program, opt_level);
+    let module = LLVMCodeGen::compile_program(&context, &program, opt_level, tm);
     Ok(module.print_to_string().to_string())
 }
 
@@ -44,24 +64,12 @@ pub fn compile_to_object(
     opt_level: OptimizationLevel,
     output_path: &Path,
 ) -> Result<std::path::PathBuf, CompilerError> {
-    let program = compile(source)?;
+    // The program is checked and generated for the machine that emits it, so
+    // `sizeof` and struct layout match the object file.
+    let tm = host_target_machine(opt_level).map_err(|e| CompilerError::Codegen(e.to_string()))?;
+    let program = compile_for_target(source, &tm)?;
     let context = Context::create();
-    let module = LLVMCodeGen::compile_program(&context, &program, opt_level);
-
-    Target::initialize_all(&InitializationConfig::default());
-    let triple = TargetMachine::get_default_triple();
-    let target = Target::from_triple(&triple)
-        .map_err(|e| CompilerError::Codegen(format!("Failed to get target: {}", e)))?;
-    let cpu = "generic";
-    let features = "";
-    let tm = target.create_target_machine(
-        &triple,
-        cpu,
-        features,
-        opt_level,
-        RelocMode::Default,
-        CodeModel::Default,
-    ).ok_or_else(|| CompilerError::Codegen("Failed to create target machine".into()))?;
+    let module = LLVMCodeGen::compile_program(&context, &program, opt_level, &tm);
 
     tm.write_to_file(&module, FileType::Object, output_path)
         .map_err(|e| CompilerError::Codegen(format!("Failed to write object file: {}", e)))?;
@@ -0,0 +1,72 @@
+/// Decodes the C escape sequences in the body of a character or string literal
+/// (without its quotes) into bytes. Returns `None` for an unknown escape or an
+/// octal/hex value that does not fit in a byte.
+pub fn unescape(body: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        let byte = match chars.next()? {
+            'n' => b'\n',
+            't' => b'\t',
+            'r' => b'\r',
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0c,
+            'v' => 0x0b,
+            '\\' => b'\\',
+            '\'' => b'\'',
+            '"' => b'"',
+            '?' => b'?',
+            d @ '0'..='7' => {
+                // Up to three octal digits, the first of which is already consumed.
+                let mut value = d.to_digit(8)?;
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                u8::try_from(value).ok()?
+            }
+            'x' => {
+                let mut value: u32 = 0;
+                let mut digits = 0;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(16)) {
+                    value = value.checked_mul(16)? + digit;
+                    digits += 1;
+                    chars.next();
+                }
+                if digits == 0 {
+                    return None;
+                }
+                u8::try_from(value).ok()?
+            }
+            _ => return None,
+        };
+        bytes.push(byte);
+    }
+    Some(bytes)
+}
+
+/// Contents of a string literal such as `"hi\n"`, without the terminating NUL.
+pub fn string_literal_value(text: &str) -> Option<Vec<u8>> {
+    unescape(text.strip_prefix('"')?.strip_suffix('"')?)
+}
+
+/// Value of a character literal such as `'a'` or `'\n'`, as the `int` C gives it.
+/// `char` is signed, so bytes above 0x7f yield negative values.
+pub fn char_literal_value(text: &str) -> Option<i64> {
+    let body = text.strip_prefix('\'')?.strip_suffix('\'')?;
+    match unescape(body)?.as_slice() {
+        [byte] => Some(*byte as i8 as i64),
+        _ => None,
+    }
+}
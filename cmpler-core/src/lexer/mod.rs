@@ -1,5 +1,8 @@
 pub mod error;
+pub mod escape;
+#[allow(clippy::module_inception)]
 pub mod lexer;
+pub mod number;
 pub mod token;
 
 pub use lexer::lex;
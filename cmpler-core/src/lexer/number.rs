@@ -0,0 +1,45 @@
+use crate::semantic::symbol_table::Type;
+
+/// Value and type of an integer literal such as `42`, `0x1f`, `017` or `10ul`.
+/// The type is the first of the candidates allowed by the suffix that can hold
+/// the value; octal and hexadecimal literals may also take unsigned types.
+/// Returns `None` when the value does not fit in 64 bits.
+pub fn integer_literal_value(text: &str) -> Option<(i64, Type)> {
+    let digits_end = text.trim_end_matches(['u', 'U', 'l', 'L']).len();
+    let (digits, suffix) = text.split_at(digits_end);
+    let (digits, radix) = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        (hex, 16)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (&digits[1..], 8)
+    } else {
+        (digits, 10)
+    };
+    let value = u64::from_str_radix(digits, radix).ok()?;
+
+    let suffix = suffix.to_ascii_lowercase();
+    let unsigned = suffix.contains('u');
+    let rank = suffix.matches('l').count();
+    let candidates: &[Type] = match (unsigned, rank) {
+        (false, 0) if radix == 10 => &[Type::Int, Type::Long, Type::LongLong],
+        (false, 0) => &[Type::Int, Type::UInt, Type::Long, Type::ULong, Type::LongLong, Type::ULongLong],
+        (false, 1) if radix == 10 => &[Type::Long, Type::LongLong],
+        (false, 1) => &[Type::Long, Type::ULong, Type::LongLong, Type::ULongLong],
+        (false, _) if radix == 10 => &[Type::LongLong],
+        (false, _) => &[Type::LongLong, Type::ULongLong],
+        (true, 0) => &[Type::UInt, Type::ULong, Type::ULongLong],
+        (true, 1) => &[Type::ULong, Type::ULongLong],
+        (true, _) => &[Type::ULongLong],
+    };
+    // A decimal literal too large for every signed candidate still gets a type,
+    // as it does with GCC and Clang.
+    let ty = candidates.iter().find(|ty| ty.fits(value)).cloned().unwrap_or(Type::ULongLong);
+    Some((value as i64, ty))
+}
+
+/// Value and type of a floating constant such as `1.5`, `.5e-3` or `2.0f`. An
+/// `f` suffix makes it a `float`; otherwise, as with `l`, it is a `double`.
+pub fn float_literal_value(text: &str) -> Option<(f64, Type)> {
+    let digits = text.trim_end_matches(['f', 'F', 'l', 'L']);
+    let ty = if text.ends_with(['f', 'F']) { Type::Float } else { Type::Double };
+    Some((digits.parse().ok()?, ty))
+}
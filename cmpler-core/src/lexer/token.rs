@@ -10,21 +10,78 @@ pub enum TokenKind {
     Else,
     #[token("while")]
     While,
+    #[token("do")]
+    Do,
+    #[token("switch")]
+    Switch,
+    #[token("case")]
+    Case,
+    #[token("default")]
+    Default,
     #[token("for")]
     For,
     #[token("return")]
     Return,
+    #[token("break")]
+    Break,
+    #[token("continue")]
+    Continue,
     #[token("int")]
     Int,
+    #[token("char")]
+    Char,
     #[token("void")]
     Void,
+    #[token("struct")]
+    Struct,
+    #[token("union")]
+    Union,
+    #[token("enum")]
+    Enum,
+    #[token("typedef")]
+    Typedef,
+    #[token("sizeof")]
+    Sizeof,
+    #[token("static")]
+    Static,
+    #[token("extern")]
+    Extern,
+    #[token("const")]
+    Const,
+    #[token("volatile")]
+    Volatile,
+    #[token("short")]
+    Short,
+    #[token("long")]
+    Long,
+    #[token("signed")]
+    Signed,
+    #[token("unsigned")]
+    Unsigned,
+    #[token("float")]
+    Float,
+    #[token("double")]
+    Double,
 
     #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*", priority = 2)]
     Identifier,
 
-    #[regex(r"[0-9]+")]
+    /// Decimal, octal or hexadecimal, with an optional `u`/`l`/`ll` suffix.
+    #[regex(r"(0[xX][0-9a-fA-F]+|[0-9]+)([uU](l|L|ll|LL)?|(l|L|ll|LL)[uU]?)?")]
     IntegerLiteral,
 
+    /// A decimal floating constant with a fraction and/or an exponent, and an
+    /// optional `f` or `l` suffix.
+    #[regex(r"([0-9]+\.[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?[fFlL]?")]
+    #[regex(r"[0-9]+[eE][+-]?[0-9]+[fFlL]?")]
+    FloatLiteral,
+
+    #[regex(r"'([^'\\\n]|\\[^\n])+'")]
+    CharLiteral,
+
+    #[regex(r#""([^"\\\n]|\\[^\n])*""#)]
+    StringLiteral,
+
     #[token("+")]
     Plus,
     #[token("-")]
@@ -33,8 +90,44 @@ pub enum TokenKind {
     Star,
     #[token("/")]
     Slash,
+    #[token("%")]
+    Percent,
+    #[token("&")]
+    Ampersand,
+    #[token("|")]
+    Pipe,
+    #[token("^")]
+    Caret,
+    #[token("<<")]
+    ShiftLeft,
+    #[token(">>")]
+    ShiftRight,
     #[token("=")]
     Assign,
+    #[token("+=")]
+    PlusAssign,
+    #[token("-=")]
+    MinusAssign,
+    #[token("*=")]
+    StarAssign,
+    #[token("/=")]
+    SlashAssign,
+    #[token("%=")]
+    PercentAssign,
+    #[token("&=")]
+    AmpersandAssign,
+    #[token("|=")]
+    PipeAssign,
+    #[token("^=")]
+    CaretAssign,
+    #[token("<<=")]
+    ShiftLeftAssign,
+    #[token(">>=")]
+    ShiftRightAssign,
+    #[token("++")]
+    PlusPlus,
+    #[token("--")]
+    MinusMinus,
     #[token("==")]
     Equal,
     #[token("!=")]
@@ -53,9 +146,21 @@ pub enum TokenKind {
     LogicalOr,
     #[token("!")]
     LogicalNot,
+    #[token("~")]
+    Tilde,
 
     #[token(";")]
     Semicolon,
+    #[token(":")]
+    Colon,
+    #[token("...")]
+    Ellipsis,
+    #[token(".")]
+    Dot,
+    #[token("->")]
+    Arrow,
+    #[token("?")]
+    Question,
     #[token(",")]
     Comma,
     #[token("(")]
@@ -66,10 +171,38 @@ pub enum TokenKind {
     LBrace,
     #[token("}")]
     RBrace,
+    #[token("[")]
+    LBracket,
+    #[token("]")]
+    RBracket,
 
     Error,
 }
 
+impl TokenKind {
+    /// Returns true for `=` and the compound assignment operators.
+    pub fn is_assignment(&self) -> bool {
+        *self == TokenKind::Assign || self.compound_op().is_some()
+    }
+
+    /// Maps a compound assignment operator such as `+=` to its binary operator.
+    pub fn compound_op(&self) -> Option<TokenKind> {
+        match self {
+            TokenKind::PlusAssign => Some(TokenKind::Plus),
+            TokenKind::MinusAssign => Some(TokenKind::Minus),
+            TokenKind::StarAssign => Some(TokenKind::Star),
+            TokenKind::SlashAssign => Some(TokenKind::Slash),
+            TokenKind::PercentAssign => Some(TokenKind::Percent),
+            TokenKind::AmpersandAssign => Some(TokenKind::Ampersand),
+            TokenKind::PipeAssign => Some(TokenKind::Pipe),
+            TokenKind::CaretAssign => Some(TokenKind::Caret),
+            TokenKind::ShiftLeftAssign => Some(TokenKind::ShiftLeft),
+            TokenKind::ShiftRightAssign => Some(TokenKind::ShiftRight),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     pub kind: TokenKind,
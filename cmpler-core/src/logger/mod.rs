@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod logger;
 
 pub use logger::init_logger;
@@ -1,4 +1,5 @@
 pub mod error;
+#[allow(clippy::module_inception)]
 mod parser;
 
 pub use parser::Parser;
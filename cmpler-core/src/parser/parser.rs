@@ -1,121 +1,887 @@
-use crate::ast::nodes::{Program, Decl, Stmt, Expr};
+use crate::ast::nodes::{Program, Decl, Stmt, Expr, StorageClass};
 use crate::lexer::{Token, TokenKind};
+use crate::lexer::escape::{char_literal_value, string_literal_value};
+use crate::lexer::number::{float_literal_value, integer_literal_value};
 use crate::utils::span::Span;
 use crate::parser::error::ParseError;
+use crate::semantic::symbol_table::{Qualifiers, Type};
+use crate::semantic::const_eval::eval_const;
+use crate::semantic::layout::{Layout, TargetLayout};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::vec;
 use tracing::instrument;
 
+/// Precedence of the conditional operator `?:`, between assignment and `||`.
+const TERNARY_PREC: u8 = 1;
+
 const OPS: &[(TokenKind, u8, bool)] = &[
-    (TokenKind::Assign,       0, true),
-    (TokenKind::Equal,        1, false),
-    (TokenKind::NotEqual,     1, false),
-    (TokenKind::Less,         2, false),
-    (TokenKind::LessEqual,    2, false),
-    (TokenKind::Greater,      2, false),
-    (TokenKind::GreaterEqual, 2, false),
-    (TokenKind::Plus,         3, false),
-    (TokenKind::Minus,        3, false),
-    (TokenKind::Star,         4, false),
-    (TokenKind::Slash,        4, false),
+    (TokenKind::Assign,           0, true),
+    (TokenKind::PlusAssign,       0, true),
+    (TokenKind::MinusAssign,      0, true),
+    (TokenKind::StarAssign,       0, true),
+    (TokenKind::SlashAssign,      0, true),
+    (TokenKind::PercentAssign,    0, true),
+    (TokenKind::AmpersandAssign,  0, true),
+    (TokenKind::PipeAssign,       0, true),
+    (TokenKind::CaretAssign,      0, true),
+    (TokenKind::ShiftLeftAssign,  0, true),
+    (TokenKind::ShiftRightAssign, 0, true),
+    (TokenKind::LogicalOr,        2, false),
+    (TokenKind::LogicalAnd,       3, false),
+    (TokenKind::Pipe,             4, false),
+    (TokenKind::Caret,            5, false),
+    (TokenKind::Ampersand,        6, false),
+    (TokenKind::Equal,            7, false),
+    (TokenKind::NotEqual,         7, false),
+    (TokenKind::Less,             8, false),
+    (TokenKind::LessEqual,        8, false),
+    (TokenKind::Greater,          8, false),
+    (TokenKind::GreaterEqual,     8, false),
+    (TokenKind::ShiftLeft,        9, false),
+    (TokenKind::ShiftRight,       9, false),
+    (TokenKind::Plus,            10, false),
+    (TokenKind::Minus,           10, false),
+    (TokenKind::Star,            11, false),
+    (TokenKind::Slash,           11, false),
+    (TokenKind::Percent,         11, false),
 ];
 
+/// What an identifier means to the parser. Typedef names and enumeration
+/// constants change how the source is parsed; other declarations are recorded
+/// so that they can hide them in inner scopes, and with their type so that
+/// `sizeof` them can be folded in array sizes.
+#[derive(Debug, Clone)]
+enum Binding {
+    Object(Type),
+    Typedef(Type),
+    Constant(i64),
+}
+
 pub struct Parser {
     tokens: Vec<Token>,
     pos: usize,
+    /// Identifiers declared in each enclosing scope, file scope first.
+    scopes: Vec<HashMap<String, Binding>>,
+    /// Struct and union tags defined in each enclosing block, with the unique tag
+    /// each was given. Tags defined at file scope keep their own names.
+    tag_scopes: Vec<HashMap<String, String>>,
+    /// Number of tags invented so far for anonymous structs and unions and for
+    /// those defined in a block.
+    anonymous_tags: usize,
+    /// Structs and unions defined in the current function's blocks, which are
+    /// emitted at file scope ahead of it.
+    hoisted: Vec<Decl>,
+    /// Members of each struct and union defined so far, for `sizeof`.
+    tags: HashMap<String, Vec<(String, Type)>>,
+    layout: TargetLayout,
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, pos: 0 }
+    /// Creates a parser for `tokens` that folds `sizeof` for the target with `layout`.
+    pub fn new(tokens: Vec<Token>, layout: TargetLayout) -> Self {
+        Parser { tokens, pos: 0, scopes: vec![HashMap::new()], tag_scopes: vec![HashMap::new()], anonymous_tags: 0, hoisted: Vec::new(), tags: HashMap::new(), layout }
     }
 
     #[instrument(level = "info", skip(self))]
     pub fn parse_program(&mut self) -> Result<Program, ParseError> {
         let mut decls = Vec::new();
         while !self.is_eof() {
-            decls.push(self.parse_decl()?);
+            let decl = self.parse_decl()?;
+            decls.append(&mut self.hoisted);
+            decls.extend(decl);
         }
         Ok(Program { decls })
     }
 
+    /// Parses a function definition or prototype, or a global declaration with one
+    /// or more comma-separated declarators, each yielding its own `Decl::Var`, or
+    /// a `typedef`. A struct, union or enum definition may precede the
+    /// declarators, or stand alone.
     #[instrument(level = "debug", skip(self))]
-    fn parse_decl(&mut self) -> Result<Decl, ParseError> {
-        let int_tok = self.expect(TokenKind::Int)?;
-        let name_tok = self.expect_identifier("declaration name")?;
-        let span_start = int_tok.span.start;
-        let span = Span { start: span_start, end: name_tok.span.end };
+    fn parse_decl(&mut self) -> Result<Vec<Decl>, ParseError> {
+        let mut decls = Vec::new();
+        let storage = self.parse_storage_class();
+        let is_typedef = storage.is_none() && self.consume(TokenKind::Typedef);
+        let qualifiers = self.parse_qualifiers();
+        let (base, ty_span) = if self.at_tag_definition() {
+            let (def, ty) = self.parse_tag_definition()?;
+            let span = def.span();
+            decls.push(def);
+            (ty.qualified(self.parse_qualifiers()), span)
+        } else {
+            self.parse_type()?
+        };
+        let base = base.qualified(qualifiers);
+        // `struct point;` only declares the tag.
+        if (matches!(base.unqualified(), Type::Struct(_) | Type::Union(_)) || !decls.is_empty()) && self.consume(TokenKind::Semicolon) {
+            return Ok(decls);
+        }
+        if is_typedef {
+            loop {
+                let (ty, name_tok, open_size) = self.parse_declarator(&base)?;
+                let span = Span { start: ty_span.start, end: name_tok.span.end };
+                let ty = Self::complete_array(ty, open_size, None, None, span)?;
+                self.bind(&name_tok.text, Binding::Typedef(ty.clone()));
+                decls.push(Decl::Typedef { name: name_tok.text, ty, span });
+                if !self.consume(TokenKind::Comma) { break; }
+            }
+            self.expect(TokenKind::Semicolon)?;
+            return Ok(decls);
+        }
+        let (ty, name_tok, open_size) = self.parse_declarator(&base)?;
+        let span = Span { start: ty_span.start, end: name_tok.span.end };
+        self.bind(&name_tok.text, Binding::Object(ty.clone()));
 
-        if self.consume(TokenKind::LParen) {
-            self.expect(TokenKind::RParen)?;
-            let body = self.parse_block()?;
-            Ok(Decl::Function { name: name_tok.text.clone(), params: Vec::new(), body, span })
+        if !open_size && !ty.is_array() && self.consume(TokenKind::LParen) {
+            self.enter_scope();
+            let (params, variadic) = self.parse_params()?;
+            let body = if self.consume(TokenKind::Semicolon) { None } else { Some(self.parse_block()?) };
+            // `int f() { ... }` defines a function without parameters; only a
+            // declaration leaves them unspecified.
+            let variadic = variadic && !(params.is_empty() && body.is_some());
+            self.exit_scope();
+            // Qualifiers on a return value have no effect, as it is not an object.
+            let ret_ty = ty.unqualified().clone();
+            let fn_ty = Type::Function { ret: Box::new(ret_ty.clone()), params: params.iter().map(|(_, ty)| ty.clone()).collect(), variadic };
+            self.bind(&name_tok.text, Binding::Object(fn_ty));
+            decls.push(Decl::Function { name: name_tok.text.clone(), ret_ty, params, variadic, body, storage, span });
+            Ok(decls)
         } else {
-            self.expect(TokenKind::Assign)?;
-            let init = self.parse_expr()?;
+            if *ty.unqualified() == Type::Void {
+                let found = self.peek_kind().unwrap_or(TokenKind::Error);
+                let span = self.peek().map(|t| t.span).unwrap_or(name_tok.span);
+                return Err(ParseError::Expected { expected: "function parameter list after void declaration".into(), found, span });
+            }
+            let (init, span) = self.parse_initializer(&ty, open_size, span)?;
+            let ty = Self::complete_array(ty, open_size, init.as_ref(), storage, span)?;
+            self.bind(&name_tok.text, Binding::Object(ty.clone()));
+            decls.push(Decl::Var { name: name_tok.text.clone(), ty, init: init.map(Box::new), storage, span });
+            while self.consume(TokenKind::Comma) {
+                let (ty, name_tok, open_size) = self.parse_declarator(&base)?;
+                let span = Span { start: ty_span.start, end: name_tok.span.end };
+                self.bind(&name_tok.text, Binding::Object(ty.clone()));
+                Self::expect_object_type(&ty, span)?;
+                let (init, span) = self.parse_initializer(&ty, open_size, span)?;
+                let ty = Self::complete_array(ty, open_size, init.as_ref(), storage, span)?;
+                self.bind(&name_tok.text, Binding::Object(ty.clone()));
+                decls.push(Decl::Var { name: name_tok.text.clone(), ty, init: init.map(Box::new), storage, span });
+            }
+            self.expect(TokenKind::Semicolon)?;
+            Ok(decls)
+        }
+    }
+
+    /// Parses the optional `= expr` or `= { ... }` after a declarator of type `ty`,
+    /// extending `span` over it.
+    fn parse_initializer(&mut self, ty: &Type, open_size: bool, span: Span) -> Result<(Option<Expr>, Span), ParseError> {
+        if self.consume(TokenKind::Assign) {
+            let init = self.parse_initializer_value()?;
+            let span = Span { start: span.start, end: init.span().end };
+            let init = self.brace_initializer(ty, open_size, init);
+            Ok((Some(init), span))
+        } else {
+            Ok((None, span))
+        }
+    }
+
+    /// Parses an expression or a brace-enclosed list of initializers, which may
+    /// nest and may end in a trailing comma.
+    fn parse_initializer_value(&mut self) -> Result<Expr, ParseError> {
+        let Some(open) = self.peek().filter(|t| t.kind == TokenKind::LBrace).cloned() else {
+            return self.parse_expr();
+        };
+        self.pos += 1;
+        let mut items = Vec::new();
+        while self.peek_kind() != Some(TokenKind::RBrace) {
+            items.push(self.parse_initializer_value()?);
+            if !self.consume(TokenKind::Comma) { break; }
+        }
+        let close = self.expect(TokenKind::RBrace)?;
+        Ok(Expr::InitList { items, span: Span { start: open.span.start, end: close.span.end } })
+    }
+
+    /// Restores the braces an initializer list for `ty` may leave out around its
+    /// sub-aggregates, so that `int m[2][2] = {1, 2, 3, 4}` reads as
+    /// `{{1, 2}, {3, 4}}`. Items left over once every element or member has its
+    /// initializer stay at the end of the list, for the analyzer to report.
+    fn brace_initializer(&self, ty: &Type, open_size: bool, init: Expr) -> Expr {
+        match init {
+            Expr::InitList { items, span } if Self::is_aggregate(ty) => {
+                let mut rest = items.into_iter().peekable();
+                let mut items = self.brace_items(ty, open_size, &mut rest);
+                items.extend(rest);
+                Expr::InitList { items, span }
+            }
+            init => init,
+        }
+    }
+
+    /// Takes the initializers of the elements or members of `ty` from the front of
+    /// `items`, all of them for an array of open size. An element or member that
+    /// is itself an aggregate but has no braces of its own takes as many of the
+    /// following items as it needs.
+    fn brace_items(&self, ty: &Type, open_size: bool, items: &mut Peekable<vec::IntoIter<Expr>>) -> Vec<Expr> {
+        let members = match ty.unqualified() {
+            Type::Struct(tag) => self.members(tag).unwrap_or_default().to_vec(),
+            // Only the first member of a union is initialized.
+            Type::Union(tag) => self.members(tag).unwrap_or_default().iter().take(1).cloned().collect(),
+            _ => Vec::new(),
+        };
+        let mut braced = Vec::new();
+        while let Some(item) = items.peek() {
+            let slot = match ty.unqualified() {
+                Type::Array(elem, len) if open_size || braced.len() < *len => (**elem).clone(),
+                Type::Struct(_) | Type::Union(_) if braced.len() < members.len() => members[braced.len()].1.clone(),
+                _ => break,
+            };
+            let elided = match (slot.unqualified(), item) {
+                (_, Expr::InitList { .. }) => false,
+                (Type::Array(elem, _), Expr::StringLiteral { .. }) => Self::is_aggregate(elem),
+                (Type::Array(..), _) => true,
+                // A struct or union may be initialized from a value of its own type.
+                (Type::Struct(_) | Type::Union(_), item) => {
+                    !matches!(self.operand_type(item), Some(item_ty) if item_ty.unqualified() == slot.unqualified())
+                }
+                _ => false,
+            };
+            if elided {
+                let span = item.span();
+                let items = self.brace_items(&slot, false, items);
+                braced.push(Expr::InitList { items, span });
+            } else {
+                let item = items.next().unwrap();
+                braced.push(self.brace_initializer(&slot, false, item));
+            }
+        }
+        braced
+    }
+
+    fn is_aggregate(ty: &Type) -> bool {
+        matches!(ty.unqualified(), Type::Array(..) | Type::Struct(_) | Type::Union(_))
+    }
+
+    /// Takes the size of an array declared as `a[]` from its initializer: the
+    /// number of list elements, or the length of a string including its NUL. An
+    /// `extern` declaration without one leaves the size to the definition, and
+    /// keeps 0 for it.
+    fn complete_array(ty: Type, open_size: bool, init: Option<&Expr>, storage: Option<StorageClass>, span: Span) -> Result<Type, ParseError> {
+        if !open_size || (init.is_none() && storage == Some(StorageClass::Extern)) {
+            return Ok(ty);
+        }
+        let Type::Array(elem, _) = ty else { unreachable!("only arrays may be open_size") };
+        let len = match init {
+            Some(Expr::InitList { items, .. }) => items.len(),
+            Some(Expr::StringLiteral { value, .. }) => value.len() + 1,
+            _ => return Err(ParseError::Expected { expected: "array size or initializer".into(), found: TokenKind::RBracket, span }),
+        };
+        Ok(Type::Array(elem, len))
+    }
+
+    /// Parses `int a = 1, *p, c = 3;` inside a block into one `LocalVar` per declarator.
+    /// A `typedef` in a block only binds its names for the rest of the block, as
+    /// do the tag and constants of a struct, union or enum defined there.
+    fn parse_local_decl(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let storage = self.parse_storage_class();
+        let is_typedef = storage.is_none() && self.consume(TokenKind::Typedef);
+        let qualifiers = self.parse_qualifiers();
+        let (base, ty_span) = if self.at_tag_definition() {
+            let (def, ty) = self.parse_tag_definition()?;
+            let span = def.span();
+            // Enumeration constants were bound by the parser, so only a struct or
+            // union definition needs to reach the later passes.
+            if let Decl::Struct { .. } = def {
+                self.hoisted.push(def);
+            }
+            if self.consume(TokenKind::Semicolon) {
+                return Ok(Vec::new());
+            }
+            (ty.qualified(self.parse_qualifiers()), span)
+        } else {
+            self.parse_type()?
+        };
+        let base = base.qualified(qualifiers);
+        let mut stmts = Vec::new();
+        loop {
+            if is_typedef {
+                let (ty, name_tok, open_size) = self.parse_declarator(&base)?;
+                let span = Span { start: ty_span.start, end: name_tok.span.end };
+                let ty = Self::complete_array(ty, open_size, None, None, span)?;
+                self.bind(&name_tok.text, Binding::Typedef(ty));
+                if !self.consume(TokenKind::Comma) { break; }
+                continue;
+            }
+            let (ty, name_tok, open_size) = self.parse_declarator(&base)?;
+            let span = Span { start: ty_span.start, end: name_tok.span.end };
+            Self::expect_object_type(&ty, span)?;
+            self.bind(&name_tok.text, Binding::Object(ty.clone()));
+            let (init, span) = self.parse_initializer(&ty, open_size, span)?;
+            let ty = Self::complete_array(ty, open_size, init.as_ref(), storage, span)?;
+            self.bind(&name_tok.text, Binding::Object(ty.clone()));
+            stmts.push(Stmt::LocalVar { name: name_tok.text.clone(), ty, init, storage, span });
+            if !self.consume(TokenKind::Comma) { break; }
+        }
+        self.expect(TokenKind::Semicolon)?;
+        Ok(stmts)
+    }
+
+    /// Whether a struct, union or enum definition starts here, with or without a tag.
+    fn at_tag_definition(&self) -> bool {
+        matches!(self.peek_kind(), Some(TokenKind::Struct | TokenKind::Union | TokenKind::Enum))
+            && (self.peek_kind_at(1) == Some(TokenKind::LBrace)
+                || (self.peek_kind_at(1) == Some(TokenKind::Identifier) && self.peek_kind_at(2) == Some(TokenKind::LBrace)))
+    }
+
+    /// Parses a struct, union or enum definition up to its closing brace, returning
+    /// it along with the type it defines. A struct or union without a tag, or
+    /// defined in a block, is given one that no identifier can spell, so that
+    /// blocks may each define their own `struct s`.
+    fn parse_tag_definition(&mut self) -> Result<(Decl, Type), ParseError> {
+        let kw = self.bump().unwrap();
+        let name = if self.peek_kind() == Some(TokenKind::Identifier) { Some(self.bump().unwrap().text) } else { None };
+        if kw.kind == TokenKind::Enum {
+            let def = self.parse_enum_body(kw.span, name)?;
+            return Ok((def, Type::Int));
+        }
+        let name = match name {
+            Some(name) if self.tag_scopes.len() == 1 => name,
+            Some(name) => {
+                self.anonymous_tags += 1;
+                let unique = format!("{}.{}", name, self.anonymous_tags);
+                self.tag_scopes.last_mut().unwrap().insert(name, unique.clone());
+                unique
+            }
+            None => {
+                self.anonymous_tags += 1;
+                format!("anon.{}", self.anonymous_tags)
+            }
+        };
+        let is_union = kw.kind == TokenKind::Union;
+        let members = self.parse_members()?;
+        let span = Span { start: kw.span.start, end: self.tokens[self.pos - 1].span.end };
+        let ty = if is_union { Type::Union(name.clone()) } else { Type::Struct(name.clone()) };
+        self.tags.insert(name.clone(), members.clone());
+        Ok((Decl::Struct { name, members, is_union, span }, ty))
+    }
+
+    /// Parses `{ A, B = 5, C }` after `enum`. A constant without a value is one
+    /// more than the previous, starting from 0; each may be used by those after it.
+    fn parse_enum_body(&mut self, start: Span, name: Option<String>) -> Result<Decl, ParseError> {
+        self.expect(TokenKind::LBrace)?;
+        let mut constants = Vec::new();
+        let mut next = 0;
+        loop {
+            let tok = self.expect_identifier("enumeration constant")?;
+            if self.consume(TokenKind::Assign) {
+                let value = self.parse_expr()?;
+                next = eval_const(&value, &mut |e| self.sizeof_value(e)).ok_or_else(|| ParseError::Expected {
+                    expected: "integer constant enumerator value".into(),
+                    found: self.tokens[self.pos - 1].kind.clone(),
+                    span: value.span(),
+                })?;
+            }
+            self.bind(&tok.text, Binding::Constant(next));
+            constants.push((tok.text, next));
+            next = next.wrapping_add(1);
+            if !self.consume(TokenKind::Comma) || self.peek_kind() == Some(TokenKind::RBrace) { break; }
+        }
+        let close = self.expect(TokenKind::RBrace)?;
+        Ok(Decl::Enum { name, constants, span: Span { start: start.start, end: close.span.end } })
+    }
+
+    /// Parses the `{ members }` of a struct or union. Members are declared like
+    /// variables, without initializers.
+    fn parse_members(&mut self) -> Result<Vec<(String, Type)>, ParseError> {
+        self.expect(TokenKind::LBrace)?;
+        let mut members = Vec::new();
+        while !self.consume(TokenKind::RBrace) {
+            let (base, ty_span) = self.parse_type()?;
+            loop {
+                let (ty, name_tok, open_size) = self.parse_declarator(&base)?;
+                let span = Span { start: ty_span.start, end: name_tok.span.end };
+                Self::expect_object_type(&ty, span)?;
+                let ty = Self::complete_array(ty, open_size, None, None, span)?;
+                members.push((name_tok.text, ty));
+                if !self.consume(TokenKind::Comma) { break; }
+            }
             self.expect(TokenKind::Semicolon)?;
-            Ok(Decl::Var { name: name_tok.text.clone(), init: Box::new(init), span })
         }
+        Ok(members)
+    }
+
+    /// Parses a type specifier along with any `const` or `volatile` before or
+    /// after it, as in `const char` or `int volatile`.
+    fn parse_type(&mut self) -> Result<(Type, Span), ParseError> {
+        let start = self.peek().map(|t| t.span);
+        let leading = self.parse_qualifiers();
+        let (ty, span) = self.parse_type_specifier()?;
+        let qualifiers = leading.union(self.parse_qualifiers());
+        let span = Span { start: start.map_or(span.start, |s| s.start), end: self.tokens[self.pos - 1].span.end };
+        Ok((ty.qualified(qualifiers), span))
+    }
+
+    /// Parses any number of `const` and `volatile` keywords.
+    fn parse_qualifiers(&mut self) -> Qualifiers {
+        let mut qualifiers = Qualifiers::default();
+        loop {
+            if self.consume(TokenKind::Const) {
+                qualifiers.is_const = true;
+            } else if self.consume(TokenKind::Volatile) {
+                qualifiers.is_volatile = true;
+            } else {
+                return qualifiers;
+            }
+        }
+    }
+
+    /// Parses an optional `static` or `extern` at the start of a declaration.
+    fn parse_storage_class(&mut self) -> Option<StorageClass> {
+        if self.consume(TokenKind::Static) {
+            Some(StorageClass::Static)
+        } else if self.consume(TokenKind::Extern) {
+            Some(StorageClass::Extern)
+        } else {
+            None
+        }
+    }
+
+    fn parse_type_specifier(&mut self) -> Result<(Type, Span), ParseError> {
+        let tok = self.peek().cloned().ok_or(ParseError::Eof)?;
+        let ty = match tok.kind {
+            TokenKind::Void => Type::Void,
+            TokenKind::Float => Type::Float,
+            TokenKind::Double => Type::Double,
+            TokenKind::Struct | TokenKind::Union | TokenKind::Enum => {
+                self.pos += 1;
+                let keyword = format!("{:?}", tok.kind).to_lowercase();
+                let tag = self.expect_identifier(&format!("{} tag", keyword))?;
+                if self.peek_kind() == Some(TokenKind::LBrace) {
+                    let span = self.peek().unwrap().span;
+                    return Err(ParseError::Expected { expected: format!("{} definition at the start of a declaration", keyword), found: TokenKind::LBrace, span });
+                }
+                let span = Span { start: tok.span.start, end: tag.span.end };
+                let tag = self.tag_named(&tag.text);
+                // Enumerated types are compatible with `int`.
+                let ty = match tok.kind {
+                    TokenKind::Struct => Type::Struct(tag),
+                    TokenKind::Union => Type::Union(tag),
+                    _ => Type::Int,
+                };
+                return Ok((ty, span));
+            }
+            TokenKind::Identifier => match self.typedef_named(&tok.text) {
+                Some(ty) => ty,
+                None => return Err(ParseError::Expected { expected: "type specifier".into(), found: tok.kind, span: tok.span }),
+            },
+            TokenKind::Int | TokenKind::Char | TokenKind::Short | TokenKind::Long | TokenKind::Signed | TokenKind::Unsigned => {
+                return self.parse_integer_type();
+            }
+            other => return Err(ParseError::Expected { expected: "type specifier".into(), found: other, span: tok.span }),
+        };
+        self.pos += 1;
+        Ok((ty, tok.span))
+    }
+
+    /// Parses the keywords of an integer type, which may come in any order:
+    /// `unsigned long int` and `long unsigned` are the same type. `char` with no
+    /// sign keyword is signed, and `int` is implied when only modifiers are given.
+    fn parse_integer_type(&mut self) -> Result<(Type, Span), ParseError> {
+        let start = self.peek().unwrap().span;
+        let (mut int, mut char, mut short, mut long, mut signed, mut unsigned) = (0, 0, 0, 0, 0, 0);
+        let mut end = start;
+        while let Some(tok) = self.peek().cloned() {
+            let count = match tok.kind {
+                TokenKind::Int => &mut int,
+                TokenKind::Char => &mut char,
+                TokenKind::Short => &mut short,
+                TokenKind::Long => &mut long,
+                TokenKind::Signed => &mut signed,
+                TokenKind::Unsigned => &mut unsigned,
+                _ => break,
+            };
+            *count += 1;
+            end = tok.span;
+            self.pos += 1;
+        }
+        let span = Span { start: start.start, end: end.end };
+        let base = match (char, short, long) {
+            (0, 0, 0) => Type::Int,
+            (1, 0, 0) if int == 0 => Type::Char,
+            (0, 1, 0) => Type::Short,
+            (0, 0, 1) => Type::Long,
+            (0, 0, 2) => Type::LongLong,
+            _ => Type::Void,
+        };
+        if base == Type::Void || int > 1 || signed + unsigned > 1 {
+            let found = self.tokens[self.pos - 1].kind.clone();
+            return Err(ParseError::Expected { expected: "valid combination of type specifiers".into(), found, span });
+        }
+        Ok((if unsigned == 1 { base.to_unsigned() } else { base }, span))
+    }
+
+    /// Wraps `base` in one `Type::Pointer` per `*` in front of a declarator name.
+    /// Qualifiers after a `*` apply to the pointer itself, as in `char *const p`.
+    fn parse_pointers(&mut self, base: &Type) -> Type {
+        let mut ty = base.clone();
+        while self.consume(TokenKind::Star) {
+            ty = Type::Pointer(Box::new(ty)).qualified(self.parse_qualifiers());
+        }
+        ty
+    }
+
+    /// Parses a declarator such as `x`, `*p` or `m[2][3]` applied to the type
+    /// specifier `base`. The flag is set when the outermost array size is omitted,
+    /// as in `a[]`; the returned type then has a placeholder size of 0.
+    fn parse_declarator(&mut self, base: &Type) -> Result<(Type, Token, bool), ParseError> {
+        let ty = self.parse_pointers(base);
+        let name_tok = self.expect_identifier("declaration name")?;
+        let (ty, open_size) = self.parse_array_suffix(ty)?;
+        Ok((ty, name_tok, open_size))
+    }
+
+    /// Parses the `[N]` suffixes after a declarator name; `int m[2][3]` is an array
+    /// of two arrays of three ints. Only the first size may be left out.
+    fn parse_array_suffix(&mut self, elem: Type) -> Result<(Type, bool), ParseError> {
+        let mut dims = Vec::new();
+        let mut open_size = false;
+        while let Some(open) = self.peek().filter(|t| t.kind == TokenKind::LBracket).cloned() {
+            self.pos += 1;
+            if dims.is_empty() && self.consume(TokenKind::RBracket) {
+                open_size = true;
+                dims.push(0);
+                continue;
+            }
+            let size = self.parse_expr()?;
+            let close = self.expect(TokenKind::RBracket)?;
+            match eval_const(&size, &mut |e| self.sizeof_value(e)) {
+                Some(n) if n > 0 => dims.push(n as usize),
+                _ => {
+                    let span = Span { start: open.span.start, end: close.span.end };
+                    return Err(ParseError::Expected { expected: "positive constant array size".into(), found: TokenKind::RBracket, span });
+                }
+            }
+        }
+        if !dims.is_empty() {
+            Self::expect_object_type(&elem, self.tokens[self.pos - 1].span)?;
+        }
+        let ty = dims.into_iter().rev().fold(elem, |ty, n| Type::Array(Box::new(ty), n));
+        Ok((ty, open_size))
+    }
+
+    /// Rejects `void` as the type of a variable, parameter or array element.
+    fn expect_object_type(ty: &Type, span: Span) -> Result<(), ParseError> {
+        if *ty.unqualified() == Type::Void {
+            return Err(ParseError::Expected { expected: "object type".into(), found: TokenKind::Void, span });
+        }
+        Ok(())
+    }
+
+    /// Parses a type name, as in a cast or `sizeof`: a type specifier followed by
+    /// a declarator without a name, such as `char *` or `int [4]`.
+    fn parse_type_name(&mut self) -> Result<Type, ParseError> {
+        let (base, ty_span) = self.parse_type()?;
+        let ty = self.parse_pointers(&base);
+        let (ty, open_size) = self.parse_array_suffix(ty)?;
+        if open_size {
+            let span = Span { start: ty_span.start, end: self.tokens[self.pos - 1].span.end };
+            return Err(ParseError::Expected { expected: "array size".into(), found: TokenKind::RBracket, span });
+        }
+        Ok(ty)
+    }
+
+    fn at_type_specifier(&self) -> bool {
+        self.is_type_specifier_at(0)
+    }
+
+    /// Whether the token `offset` places ahead starts a type: a type keyword, a
+    /// qualifier or a typedef name.
+    fn is_type_specifier_at(&self, offset: usize) -> bool {
+        let Some(tok) = self.tokens.get(self.pos + offset) else { return false };
+        if tok.kind == TokenKind::Identifier {
+            return self.typedef_named(&tok.text).is_some();
+        }
+        matches!(
+            tok.kind,
+            TokenKind::Int
+                | TokenKind::Char
+                | TokenKind::Short
+                | TokenKind::Long
+                | TokenKind::Signed
+                | TokenKind::Unsigned
+                | TokenKind::Float
+                | TokenKind::Double
+                | TokenKind::Void
+                | TokenKind::Struct
+                | TokenKind::Union
+                | TokenKind::Enum
+                | TokenKind::Const
+                | TokenKind::Volatile
+        )
+    }
+
+    /// Whether a block-scope declaration starts here, possibly with a storage
+    /// class or `typedef` in front of its type.
+    fn at_declaration(&self) -> bool {
+        self.at_type_specifier() || matches!(self.peek_kind(), Some(TokenKind::Typedef | TokenKind::Static | TokenKind::Extern))
+    }
+
+    /// The type named by `name`, if the innermost declaration of `name` is a typedef.
+    fn typedef_named(&self, name: &str) -> Option<Type> {
+        match self.binding(name) {
+            Some(Binding::Typedef(ty)) => Some(ty.clone()),
+            _ => None,
+        }
+    }
+
+    /// Folds a `sizeof` in an array size or enumerator value. Its operand is typed
+    /// from the declarations seen so far.
+    fn sizeof_value(&self, sizeof: &Expr) -> Option<u64> {
+        match sizeof {
+            Expr::SizeofType { ty, .. } => self.size_of(ty),
+            Expr::SizeofExpr { expr, .. } => self.size_of(&self.operand_type(expr)?),
+            _ => None,
+        }
+    }
+
+    /// Type of `expr` before array-to-pointer decay, following the analyzer's
+    /// rules, or `None` if it names something undeclared or the analyzer will
+    /// reject it anyway.
+    fn operand_type(&self, expr: &Expr) -> Option<Type> {
+        match expr {
+            Expr::IntLiteral { ty, .. } | Expr::FloatLiteral { ty, .. } | Expr::Cast { ty, .. } => Some(ty.clone()),
+            Expr::SizeofType { .. } | Expr::SizeofExpr { .. } => Some(Type::ULong),
+            Expr::StringLiteral { value, .. } => Some(Type::Array(Box::new(Type::Char), value.len() + 1)),
+            Expr::Var { name, .. } => match self.binding(name) {
+                Some(Binding::Object(ty)) => Some(ty.clone()),
+                _ => None,
+            },
+            Expr::Unary { op: TokenKind::PlusPlus | TokenKind::MinusMinus, operand, .. } | Expr::Postfix { operand, .. } => {
+                self.operand_type(operand)
+            }
+            Expr::Unary { op: TokenKind::Ampersand, operand, .. } => Some(Type::Pointer(Box::new(self.operand_type(operand)?))),
+            Expr::Unary { op: TokenKind::Star, operand, .. } => self.operand_type(operand)?.decay().pointee().cloned(),
+            Expr::Unary { op: TokenKind::LogicalNot, .. } => Some(Type::Int),
+            Expr::Unary { operand, .. } => Some(self.operand_type(operand)?.decay().promote()),
+            Expr::Binary { op, left, right, .. } => {
+                let lt = self.operand_type(left)?.decay();
+                let rt = self.operand_type(right)?.decay();
+                // Only arithmetic operands go through the usual arithmetic conversions.
+                let valid = (lt.is_arithmetic() && rt.is_arithmetic())
+                    || op.is_assignment()
+                    || matches!(
+                        op,
+                        TokenKind::Less
+                            | TokenKind::LessEqual
+                            | TokenKind::Greater
+                            | TokenKind::GreaterEqual
+                            | TokenKind::Equal
+                            | TokenKind::NotEqual
+                            | TokenKind::LogicalAnd
+                            | TokenKind::LogicalOr
+                    )
+                    || (matches!(op, TokenKind::Plus | TokenKind::Minus) && lt.is_pointer())
+                    || (*op == TokenKind::Plus && rt.is_pointer());
+                valid.then(|| Type::binary_result(op, &lt, &rt))
+            }
+            Expr::Conditional { then_expr, else_expr, .. } => {
+                let then_ty = self.operand_type(then_expr)?.decay();
+                let else_ty = self.operand_type(else_expr)?.decay();
+                if then_ty.is_arithmetic() && else_ty.is_arithmetic() {
+                    Some(Type::arithmetic(&then_ty, &else_ty))
+                } else if else_ty.is_pointer() && eval_const(then_expr, &mut |_| None) == Some(0) {
+                    Some(else_ty)
+                } else {
+                    Some(then_ty)
+                }
+            }
+            Expr::Call { callee, .. } => match self.binding(callee) {
+                Some(Binding::Object(Type::Function { ret, .. })) => Some((**ret).clone()),
+                _ => None,
+            },
+            Expr::Index { base, index, .. } => {
+                let base = self.operand_type(base)?.decay();
+                // `i[a]` is as valid as `a[i]`.
+                let ptr = if base.is_pointer() { base } else { self.operand_type(index)?.decay() };
+                ptr.pointee().cloned()
+            }
+            Expr::Member { base, member, arrow, .. } => {
+                let base = self.operand_type(base)?;
+                let record = if *arrow { base.decay().pointee()?.clone() } else { base };
+                let (_, ty) = self.members(record.tag()?)?.iter().find(|(name, _)| name == member)?;
+                Some(ty.clone().qualified(record.qualifiers()))
+            }
+            Expr::InitList { .. } => None,
+        }
+    }
+
+    /// The tag that `struct name` or `union name` refers to: that of the innermost
+    /// block defining `name`, or `name` itself at file scope.
+    fn tag_named(&self, name: &str) -> String {
+        self.tag_scopes.iter().rev().find_map(|scope| scope.get(name)).cloned().unwrap_or_else(|| name.to_string())
+    }
+
+    fn binding(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn bind(&mut self, name: &str, binding: Binding) {
+        self.scopes.last_mut().unwrap().insert(name.to_string(), binding);
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+        self.tag_scopes.push(HashMap::new());
+    }
+
+    fn exit_scope(&mut self) {
+        self.scopes.pop();
+        self.tag_scopes.pop();
+    }
+
+    /// Parses a parameter list after its `(`, returning the parameters and whether it
+    /// ends in `...`. Parameter names may be omitted, leaving them empty. An empty
+    /// list leaves the parameters unspecified, so it is read as `...` alone.
+    fn parse_params(&mut self) -> Result<(Vec<(String, Type)>, bool), ParseError> {
+        let mut params = Vec::new();
+        if self.consume(TokenKind::RParen) {
+            return Ok((params, true));
+        }
+        if self.peek_kind() == Some(TokenKind::Void) && self.peek_kind_at(1) == Some(TokenKind::RParen) {
+            self.pos += 2;
+            return Ok((params, false));
+        }
+        let mut variadic = false;
+        loop {
+            let (base, ty_span) = self.parse_type()?;
+            let ty = self.parse_pointers(&base);
+            Self::expect_object_type(&ty, ty_span)?;
+            let name = if self.peek_kind() == Some(TokenKind::Identifier) { self.bump().unwrap().text } else { String::new() };
+            // A parameter declared as an array is a pointer to its first element.
+            let ty = match self.parse_array_suffix(ty)? {
+                (Type::Array(elem, _), _) => Type::Pointer(elem),
+                (ty, _) => ty,
+            };
+            if !name.is_empty() { self.bind(&name, Binding::Object(ty.clone())); }
+            params.push((name, ty));
+            if !self.consume(TokenKind::Comma) { break; }
+            if self.consume(TokenKind::Ellipsis) {
+                variadic = true;
+                break;
+            }
+        }
+        self.expect(TokenKind::RParen)?;
+        Ok((params, variadic))
     }
 
     #[instrument(level = "debug", skip(self))]
     fn parse_block(&mut self) -> Result<Vec<Stmt>, ParseError> {
         self.expect(TokenKind::LBrace)?;
+        self.enter_scope();
         let mut stmts = Vec::new();
         while !self.consume(TokenKind::RBrace) {
-            stmts.push(self.parse_stmt()?);
+            if self.at_declaration() {
+                stmts.extend(self.parse_local_decl()?);
+            } else {
+                stmts.push(self.parse_stmt()?);
+            }
         }
+        self.exit_scope();
         Ok(stmts)
     }
 
+    /// Parses the body of `if`, `else`, `while`, `do` or `for`: either a braced block
+    /// or a single statement, which is kept as a one-element list.
+    fn parse_body(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        if self.peek_kind() == Some(TokenKind::LBrace) {
+            self.parse_block()
+        } else {
+            Ok(vec![self.parse_stmt()?])
+        }
+    }
+
     #[instrument(level = "debug", skip(self))]
     fn parse_stmt(&mut self) -> Result<Stmt, ParseError> {
-        if self.consume(TokenKind::LBrace) {
-            let mut stmts = Vec::new();
-            while !self.consume(TokenKind::RBrace) {
-                stmts.push(self.parse_stmt()?);
-            }
-            return Ok(Stmt::Block(stmts));
+        if self.peek_kind() == Some(TokenKind::LBrace) {
+            return Ok(Stmt::Block(self.parse_block()?));
         }
         if self.consume(TokenKind::Semicolon) {
             return Ok(Stmt::Empty);
         }
-        if self.peek_kind() == Some(TokenKind::Int) {
-            let int_tok = self.expect(TokenKind::Int)?;
-            let name_tok = self.expect_identifier("local variable name")?;
-            self.expect(TokenKind::Assign)?;
-            let init = self.parse_expr()?;
-            self.expect(TokenKind::Semicolon)?;
-            let span = Span { start: int_tok.span.start, end: init.span().end };
-            return Ok(Stmt::LocalVar { name: name_tok.text.clone(), init, span });
+        if let Some(TokenKind::Return) = self.peek_kind() {
+            let ret_tok = self.bump().unwrap();
+            let expr = if self.peek_kind() != Some(TokenKind::Semicolon) { Some(Box::new(self.parse_expr()?)) } else { None };
+            let semi = self.expect(TokenKind::Semicolon)?;
+            let span = Span { start: ret_tok.span.start, end: semi.span.end };
+            return Ok(Stmt::Return(expr, span));
         }
-        if self.consume(TokenKind::Return) {
-            let expr = self.parse_expr()?;
-            self.expect(TokenKind::Semicolon)?;
-            return Ok(Stmt::Return(Box::new(expr)));
+        if self.consume(TokenKind::Switch) {
+            self.expect(TokenKind::LParen)?;
+            let cond = self.parse_expr()?;
+            self.expect(TokenKind::RParen)?;
+            let body = self.parse_block()?;
+            return Ok(Stmt::Switch { cond: Box::new(cond), body });
+        }
+        if let Some(TokenKind::Case) = self.peek_kind() {
+            let kw = self.bump().unwrap();
+            let value = self.parse_expr()?;
+            let colon = self.expect(TokenKind::Colon)?;
+            return Ok(Stmt::Case(Box::new(value), Span { start: kw.span.start, end: colon.span.end }));
+        }
+        if let Some(TokenKind::Default) = self.peek_kind() {
+            let kw = self.bump().unwrap();
+            let colon = self.expect(TokenKind::Colon)?;
+            return Ok(Stmt::Default(Span { start: kw.span.start, end: colon.span.end }));
+        }
+        if let Some(TokenKind::Break | TokenKind::Continue) = self.peek_kind() {
+            let kw = self.bump().unwrap();
+            let semi = self.expect(TokenKind::Semicolon)?;
+            let span = Span { start: kw.span.start, end: semi.span.end };
+            return Ok(if kw.kind == TokenKind::Break { Stmt::Break(span) } else { Stmt::Continue(span) });
         }
         if self.consume(TokenKind::If) {
             self.expect(TokenKind::LParen)?;
             let cond = self.parse_expr()?;
             self.expect(TokenKind::RParen)?;
-            let then_blk = self.parse_block()?;
-            let else_blk = if self.consume(TokenKind::Else) { Some(self.parse_block()?) } else { None };
+            let then_blk = self.parse_body()?;
+            // Binding `else` greedily here attaches it to the nearest `if`, as C requires.
+            let else_blk = if self.consume(TokenKind::Else) { Some(self.parse_body()?) } else { None };
             return Ok(Stmt::If { cond: Box::new(cond), then_block: then_blk, else_block: else_blk });
         }
         if self.consume(TokenKind::While) {
             self.expect(TokenKind::LParen)?;
             let cond = self.parse_expr()?;
             self.expect(TokenKind::RParen)?;
-            let body = self.parse_block()?;
+            let body = self.parse_body()?;
             return Ok(Stmt::While(Box::new(cond), body));
         }
-        if self.consume(TokenKind::For) {
+        if self.consume(TokenKind::Do) {
+            let body = self.parse_body()?;
+            self.expect(TokenKind::While)?;
             self.expect(TokenKind::LParen)?;
-            let init = if self.peek_kind() != Some(TokenKind::Semicolon) { Some(Box::new(self.parse_expr()?)) } else { None };
+            let cond = self.parse_expr()?;
+            self.expect(TokenKind::RParen)?;
             self.expect(TokenKind::Semicolon)?;
+            return Ok(Stmt::DoWhile(body, Box::new(cond)));
+        }
+        if self.consume(TokenKind::For) {
+            self.expect(TokenKind::LParen)?;
+            // A declaration in the initializer is scoped to the loop.
+            self.enter_scope();
+            let init = if self.at_type_specifier() {
+                self.parse_local_decl()?
+            } else if self.consume(TokenKind::Semicolon) {
+                Vec::new()
+            } else {
+                let expr = self.parse_expr()?;
+                self.expect(TokenKind::Semicolon)?;
+                vec![Stmt::Expr(Box::new(expr))]
+            };
             let cond = if self.peek_kind() != Some(TokenKind::Semicolon) { Some(Box::new(self.parse_expr()?)) } else { None };
             self.expect(TokenKind::Semicolon)?;
             let inc = if self.peek_kind() != Some(TokenKind::RParen) { Some(Box::new(self.parse_expr()?)) } else { None };
             self.expect(TokenKind::RParen)?;
-            let body = self.parse_block()?;
+            let body = self.parse_body()?;
+            self.exit_scope();
             return Ok(Stmt::For { init, cond, inc, body });
         }
         let expr = self.parse_expr()?;
@@ -129,7 +895,7 @@ impl Parser {
     }
 
     fn parse_precedence(&mut self, min_prec: u8) -> Result<Expr, ParseError> {
-        let mut lhs = self.parse_primary()?;
+        let mut lhs = self.parse_unary()?;
         loop {
             let mut found = None;
             if let Some(tok) = self.peek() {
@@ -141,6 +907,20 @@ impl Parser {
                     }
                 }
             }
+            if found.is_none() && min_prec <= TERNARY_PREC && self.peek_kind() == Some(TokenKind::Question) {
+                self.bump();
+                let then_expr = self.parse_expr()?;
+                self.expect(TokenKind::Colon)?;
+                let else_expr = self.parse_precedence(TERNARY_PREC)?;
+                let span = Span { start: lhs.span().start, end: else_expr.span().end };
+                lhs = Expr::Conditional {
+                    cond: Box::new(lhs),
+                    then_expr: Box::new(then_expr),
+                    else_expr: Box::new(else_expr),
+                    span,
+                };
+                continue;
+            }
             if let Some((op, prec, right_assoc)) = found {
                 self.bump();
                 let next_min = if right_assoc { prec } else { prec + 1 };
@@ -154,11 +934,125 @@ impl Parser {
         Ok(lhs)
     }
 
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        if let Some(TokenKind::Minus | TokenKind::Plus | TokenKind::Tilde | TokenKind::LogicalNot) = self.peek_kind() {
+            let op_tok = self.bump().unwrap();
+            let operand = self.parse_unary()?;
+            let span = Span { start: op_tok.span.start, end: operand.span().end };
+            // Fold prefix operators applied to literals so that `-5` is itself a constant.
+            if let Expr::IntLiteral { value, ty, .. } = operand {
+                let (value, ty) = match op_tok.kind {
+                    TokenKind::Minus => (value.wrapping_neg(), ty.promote()),
+                    TokenKind::Plus => (value, ty.promote()),
+                    TokenKind::Tilde => (!value, ty.promote()),
+                    _ => ((value == 0) as i64, Type::Int),
+                };
+                return Ok(Expr::IntLiteral { value, ty, span });
+            }
+            if let (Expr::FloatLiteral { value, ty, .. }, TokenKind::Minus | TokenKind::Plus) = (&operand, &op_tok.kind) {
+                let value = if op_tok.kind == TokenKind::Minus { -value } else { *value };
+                return Ok(Expr::FloatLiteral { value, ty: ty.clone(), span });
+            }
+            return Ok(Expr::Unary { op: op_tok.kind, operand: Box::new(operand), span });
+        }
+        if self.peek_kind() == Some(TokenKind::Sizeof) {
+            let kw = self.bump().unwrap();
+            if self.peek_kind() == Some(TokenKind::LParen) && self.is_type_specifier_at(1) {
+                self.pos += 1;
+                let ty = self.parse_type_name()?;
+                let close = self.expect(TokenKind::RParen)?;
+                return Ok(Expr::SizeofType { ty, span: Span { start: kw.span.start, end: close.span.end } });
+            }
+            let operand = self.parse_unary()?;
+            let span = Span { start: kw.span.start, end: operand.span().end };
+            return Ok(Expr::SizeofExpr { expr: Box::new(operand), span });
+        }
+        if let Some(TokenKind::PlusPlus | TokenKind::MinusMinus | TokenKind::Ampersand | TokenKind::Star) = self.peek_kind() {
+            let op_tok = self.bump().unwrap();
+            let operand = self.parse_unary()?;
+            let span = Span { start: op_tok.span.start, end: operand.span().end };
+            return Ok(Expr::Unary { op: op_tok.kind, operand: Box::new(operand), span });
+        }
+        self.parse_postfix()
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_primary()?;
+        loop {
+            match self.peek_kind() {
+                Some(TokenKind::PlusPlus | TokenKind::MinusMinus) => {
+                    let op_tok = self.bump().unwrap();
+                    let span = Span { start: expr.span().start, end: op_tok.span.end };
+                    expr = Expr::Postfix { op: op_tok.kind, operand: Box::new(expr), span };
+                }
+                Some(TokenKind::LBracket) => {
+                    self.pos += 1;
+                    let index = self.parse_expr()?;
+                    let close = self.expect(TokenKind::RBracket)?;
+                    let span = Span { start: expr.span().start, end: close.span.end };
+                    expr = Expr::Index { base: Box::new(expr), index: Box::new(index), span };
+                }
+                Some(op @ (TokenKind::Dot | TokenKind::Arrow)) => {
+                    self.pos += 1;
+                    let member = self.expect_identifier("member name")?;
+                    let span = Span { start: expr.span().start, end: member.span.end };
+                    expr = Expr::Member { base: Box::new(expr), member: member.text, arrow: op == TokenKind::Arrow, span };
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
     fn parse_primary(&mut self) -> Result<Expr, ParseError> {
         let tok = self.bump().ok_or(ParseError::Eof)?;
         match tok.kind {
-            TokenKind::IntegerLiteral => Ok(Expr::IntLiteral { value: tok.text.parse().unwrap(), span: tok.span }),
-            TokenKind::Identifier      => Ok(Expr::Var { name: tok.text.clone(), span: tok.span }),
+            TokenKind::IntegerLiteral => match integer_literal_value(&tok.text) {
+                Some((value, ty)) => Ok(Expr::IntLiteral { value, ty, span: tok.span }),
+                None => Err(ParseError::Expected { expected: "integer constant that fits in 64 bits".into(), found: tok.kind, span: tok.span }),
+            },
+            TokenKind::FloatLiteral => match float_literal_value(&tok.text) {
+                Some((value, ty)) => Ok(Expr::FloatLiteral { value, ty, span: tok.span }),
+                None => Err(ParseError::Expected { expected: "floating constant".into(), found: tok.kind, span: tok.span }),
+            },
+            // A character constant has type `int` in C, so it is just another integer literal.
+            TokenKind::CharLiteral => match char_literal_value(&tok.text) {
+                Some(value) => Ok(Expr::IntLiteral { value, ty: Type::Int, span: tok.span }),
+                None => Err(ParseError::Expected { expected: "single-character literal".into(), found: tok.kind, span: tok.span }),
+            },
+            TokenKind::StringLiteral => {
+                // Adjacent literals are concatenated, so `"ab" "cd"` is `"abcd"`.
+                let mut value = Vec::new();
+                let mut last = tok.clone();
+                loop {
+                    match string_literal_value(&last.text) {
+                        Some(bytes) => value.extend(bytes),
+                        None => return Err(ParseError::Expected { expected: "valid string literal".into(), found: last.kind, span: last.span }),
+                    }
+                    if self.peek_kind() != Some(TokenKind::StringLiteral) { break; }
+                    last = self.bump().unwrap();
+                }
+                Ok(Expr::StringLiteral { value, span: Span { start: tok.span.start, end: last.span.end } })
+            }
+            TokenKind::Identifier if self.consume(TokenKind::LParen) => {
+                let args = self.parse_args()?;
+                let end = self.tokens[self.pos - 1].span.end;
+                Ok(Expr::Call { callee: tok.text.clone(), args, span: Span { start: tok.span.start, end } })
+            }
+            // Enumeration constants are replaced by their values, so they may appear
+            // wherever an integer constant expression is required.
+            TokenKind::Identifier => match self.binding(&tok.text) {
+                Some(Binding::Constant(value)) => Ok(Expr::IntLiteral { value: *value, ty: Type::Int, span: tok.span }),
+                _ => Ok(Expr::Var { name: tok.text.clone(), span: tok.span }),
+            },
+            // A type name after `(` makes it a cast, whose operand is a unary expression.
+            TokenKind::LParen if self.at_type_specifier() => {
+                let ty = self.parse_type_name()?;
+                self.expect(TokenKind::RParen)?;
+                let operand = self.parse_unary()?;
+                let span = Span { start: tok.span.start, end: operand.span().end };
+                // The result of a cast is a value, so qualifiers on its type are dropped.
+                Ok(Expr::Cast { ty: ty.unqualified().clone(), expr: Box::new(operand), span })
+            }
             TokenKind::LParen          => {
                 let expr = self.parse_expr()?;
                 self.expect(TokenKind::RParen)?;
@@ -168,6 +1062,19 @@ impl Parser {
         }
     }
 
+    fn parse_args(&mut self) -> Result<Vec<Expr>, ParseError> {
+        let mut args = Vec::new();
+        if self.consume(TokenKind::RParen) {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_expr()?);
+            if !self.consume(TokenKind::Comma) { break; }
+        }
+        self.expect(TokenKind::RParen)?;
+        Ok(args)
+    }
+
     fn peek(&self) -> Option<&Token> {
         self.tokens.get(self.pos)
     }
@@ -176,6 +1083,10 @@ impl Parser {
         self.peek().map(|t| t.kind.clone())
     }
 
+    fn peek_kind_at(&self, offset: usize) -> Option<TokenKind> {
+        self.tokens.get(self.pos + offset).map(|t| t.kind.clone())
+    }
+
     fn bump(&mut self) -> Option<Token> {
         let t = self.peek().cloned();
         if t.is_some() { self.pos += 1; }
@@ -208,3 +1119,13 @@ impl Parser {
         self.peek_kind().is_none()
     }
 }
+
+impl Layout for Parser {
+    fn members(&self, tag: &str) -> Option<&[(String, Type)]> {
+        self.tags.get(tag).map(Vec::as_slice)
+    }
+
+    fn target(&self) -> &TargetLayout {
+        &self.layout
+    }
+}
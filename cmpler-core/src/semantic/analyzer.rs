@@ -1,15 +1,27 @@
-use crate::ast::nodes::{Program, Decl, Stmt, Expr};
-use crate::semantic::symbol_table::{SymbolTable, Type};
+use crate::ast::nodes::{Program, Decl, Stmt, Expr, StorageClass};
+use crate::semantic::symbol_table::{SymbolKind, SymbolTable, Type};
 use crate::semantic::error::SemanticError;
+use crate::semantic::const_eval::{eval_const, eval_const_float, wrap};
+use crate::semantic::layout::{Layout, TargetLayout};
+use crate::lexer::TokenKind;
+use crate::utils::span::Span;
+use std::collections::HashSet;
 use tracing::instrument;
 
 pub struct SemanticAnalyzer {
     symbols: SymbolTable,
+    /// Name and return type of the function whose body is being checked.
+    current_fn: Option<(String, Type)>,
+    /// Number of enclosing loops around the statement being checked.
+    loop_depth: usize,
+    /// Number of enclosing `switch` statements, which also accept `break`.
+    switch_depth: usize,
 }
 
 impl SemanticAnalyzer {
-    pub fn new() -> Self {
-        SemanticAnalyzer { symbols: SymbolTable::new() }
+    /// Creates an analyzer that folds `sizeof` for the target with `layout`.
+    pub fn new(layout: TargetLayout) -> Self {
+        SemanticAnalyzer { symbols: SymbolTable::new(layout), current_fn: None, loop_depth: 0, switch_depth: 0 }
     }
 
     #[instrument(level = "info", skip(self, program))]
@@ -26,24 +38,32 @@ impl SemanticAnalyzer {
     #[instrument(level = "debug", skip(self, decl))]
     fn check_decl(&mut self, decl: &Decl) -> Result<(), SemanticError> {
         match decl {
-            Decl::Function { name: _, body, .. } => {
+            Decl::Struct { .. } | Decl::Enum { .. } | Decl::Typedef { .. } | Decl::Function { body: None, .. } => Ok(()),
+            Decl::Function { name, ret_ty, params, body: Some(body), span, .. } => {
+                if *ret_ty != Type::Void {
+                    self.expect_complete(ret_ty, *span)?;
+                }
+                self.current_fn = Some((name.clone(), ret_ty.clone()));
                 self.symbols.enter_scope();
+                for (param, ty) in params.iter() {
+                    self.expect_complete(ty, *span)?;
+                    if !param.is_empty() {
+                        self.symbols.insert_symbol(param.clone(), ty.clone(), false, *span)?;
+                    }
+                }
                 for stmt in body {
                     self.check_stmt(stmt)?;
                 }
                 self.symbols.exit_scope();
+                self.current_fn = None;
                 Ok(())
             }
-            Decl::Var { name: _, init, .. } => {
-                let ty = self.check_expr(init)?;
-                if ty != Type::Int {
-                    return Err(SemanticError::TypeMismatch {
-                        expected: "Int".into(),
-                        found: format!("{:?}", ty),
-                        span: init.span(),
-                    });
-                }
-                Ok(())
+            // A declaration of a variable defined elsewhere needs no size.
+            Decl::Var { init: None, storage: Some(StorageClass::Extern), .. } => Ok(()),
+            Decl::Var { ty, init: None, span, .. } => self.expect_complete(ty, *span),
+            Decl::Var { name, ty, init: Some(init), span, .. } => {
+                self.expect_complete(ty, *span)?;
+                self.check_initializer(name, ty, init, true)
             }
         }
     }
@@ -52,9 +72,19 @@ impl SemanticAnalyzer {
     fn check_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticError> {
         match stmt {
             Stmt::Empty => Ok(()),
-            Stmt::Return(expr) => {
-                self.check_expr(expr)?;
-                Ok(())
+            Stmt::Return(expr, span) => {
+                let (fn_name, ret_ty) = self.current_fn.clone().expect("return outside of function");
+                match expr {
+                    Some(expr) if ret_ty == Type::Void => {
+                        Err(SemanticError::ReturnValueInVoid(fn_name, expr.span()))
+                    }
+                    Some(expr) => {
+                        let ty = self.check_expr(expr)?;
+                        expect_convertible(&ty, &ret_ty, expr, expr.span())
+                    }
+                    None if ret_ty != Type::Void => Err(SemanticError::MissingReturnValue(fn_name, *span)),
+                    None => Ok(()),
+                }
             }
             Stmt::Expr(expr) => {
                 self.check_expr(expr)?;
@@ -62,13 +92,7 @@ impl SemanticAnalyzer {
             }
             Stmt::If { cond, then_block, else_block, .. } => {
                 let cond_ty = self.check_expr(cond)?;
-                if cond_ty != Type::Int {
-                    return Err(SemanticError::TypeMismatch {
-                        expected: "Int".into(),
-                        found: format!("{:?}", cond_ty),
-                        span: cond.span(),
-                    });
-                }
+                expect_scalar(&cond_ty, cond.span())?;
                 self.symbols.enter_scope();
                 for s in then_block {
                     self.check_stmt(s)?;
@@ -85,54 +109,104 @@ impl SemanticAnalyzer {
             }
             Stmt::While(cond, body) => {
                 let ty = self.check_expr(cond)?;
-                if ty != Type::Int {
-                    return Err(SemanticError::TypeMismatch {
-                        expected: "Int".into(),
-                        found: format!("{:?}", ty),
-                        span: cond.span(),
-                    });
+                expect_scalar(&ty, cond.span())?;
+                self.symbols.enter_scope();
+                self.loop_depth += 1;
+                for s in body {
+                    self.check_stmt(s)?;
                 }
+                self.loop_depth -= 1;
+                self.symbols.exit_scope();
+                Ok(())
+            }
+            Stmt::DoWhile(body, cond) => {
                 self.symbols.enter_scope();
+                self.loop_depth += 1;
                 for s in body {
                     self.check_stmt(s)?;
                 }
+                self.loop_depth -= 1;
                 self.symbols.exit_scope();
+                let ty = self.check_expr(cond)?;
+                expect_scalar(&ty, cond.span())?;
                 Ok(())
             }
             Stmt::For { init, cond, inc, body, .. } => {
+                // Declarations in the init clause live in a scope enclosing the whole
+                // loop; the body gets a nested scope of its own.
                 self.symbols.enter_scope();
-                if let Some(expr) = init {
-                    let _ = self.check_expr(expr)?;
+                for s in init {
+                    self.check_stmt(s)?;
                 }
                 if let Some(expr) = cond {
                     let ty = self.check_expr(expr)?;
-                    if ty != Type::Int {
-                        return Err(SemanticError::TypeMismatch {
-                            expected: "Int".into(),
-                            found: format!("{:?}", ty),
-                            span: expr.span(),
-                        });
-                    }
+                    expect_scalar(&ty, expr.span())?;
                 }
                 if let Some(expr) = inc {
                     let _ = self.check_expr(expr)?;
                 }
+                self.loop_depth += 1;
+                self.symbols.enter_scope();
                 for s in body {
                     self.check_stmt(s)?;
                 }
                 self.symbols.exit_scope();
+                self.loop_depth -= 1;
+                self.symbols.exit_scope();
                 Ok(())
             }
-            Stmt::LocalVar { name, init, span } => {
-                let ty = self.check_expr(init)?;
-                if ty != Type::Int {
-                    return Err(SemanticError::TypeMismatch {
-                        expected: "Int".into(),
-                        found: format!("{:?}", ty),
-                        span: *span,
-                    });
+            Stmt::Switch { cond, body } => {
+                let ty = self.check_expr(cond)?;
+                expect_integer(&ty, cond.span())?;
+                // Labels are compared in the promoted type of the controlling
+                // expression, so `case 4294967296:` is `case 0:` for an `int`.
+                let ty = ty.promote();
+                let mut seen = HashSet::new();
+                let mut has_default = false;
+                self.symbols.enter_scope();
+                self.switch_depth += 1;
+                for s in body {
+                    match s {
+                        Stmt::Case(value, span) => {
+                            self.check_expr(value)?;
+                            let v = eval_const(value, &mut |e| self.sizeof_value(e)).ok_or(SemanticError::NonConstantCase(value.span()))?;
+                            let v = wrap(v, &ty);
+                            if !seen.insert(v) {
+                                return Err(SemanticError::DuplicateCase(v, *span));
+                            }
+                        }
+                        Stmt::Default(span) => {
+                            if has_default {
+                                return Err(SemanticError::DuplicateDefault(*span));
+                            }
+                            has_default = true;
+                        }
+                        _ => self.check_stmt(s)?,
+                    }
                 }
-                self.symbols.insert_symbol(name.clone(), Type::Int, *span)?;
+                self.switch_depth -= 1;
+                self.symbols.exit_scope();
+                Ok(())
+            }
+            Stmt::Case(_, span) => Err(SemanticError::LabelOutsideSwitch("case".into(), *span)),
+            Stmt::Default(span) => Err(SemanticError::LabelOutsideSwitch("default".into(), *span)),
+            Stmt::Break(span) if self.loop_depth + self.switch_depth == 0 => Err(SemanticError::BreakOutsideLoop(*span)),
+            Stmt::Continue(span) if self.loop_depth == 0 => Err(SemanticError::ContinueOutsideLoop(*span)),
+            Stmt::Break(_) | Stmt::Continue(_) => Ok(()),
+            Stmt::LocalVar { name, ty, init, storage: Some(StorageClass::Extern), span } => {
+                if let Some(init) = init {
+                    return Err(SemanticError::InvalidInitializer(format!("extern {}", name), init.span()));
+                }
+                self.symbols.insert_symbol(name.clone(), ty.clone(), true, *span)
+            }
+            Stmt::LocalVar { name, ty, init, storage, span } => {
+                self.expect_complete(ty, *span)?;
+                // A static local is initialized once, before the program starts, so
+                // its initializer must be constant like a global's.
+                if let Some(init) = init {
+                    self.check_initializer(name, ty, init, *storage == Some(StorageClass::Static))?;
+                }
+                self.symbols.insert_symbol(name.clone(), ty.clone(), *storage == Some(StorageClass::Static), *span)?;
                 Ok(())
             }
             Stmt::Block(stmts) => {
@@ -146,29 +220,444 @@ impl SemanticAnalyzer {
         }
     }
 
-    #[instrument(level = "debug", skip(self, expr))]
+    /// Checks the initializer of a variable of type `ty`. Arrays take a brace list
+    /// with at most one element per slot, or a string literal if they hold `char`s;
+    /// missing elements are zero. Globals need constant initializers, which may
+    /// also be address constants.
+    fn check_initializer(&mut self, name: &str, ty: &Type, init: &Expr, global: bool) -> Result<(), SemanticError> {
+        match (ty.unqualified(), init) {
+            (Type::Array(elem, len), Expr::InitList { items, span }) => {
+                if items.len() > *len {
+                    return Err(SemanticError::ExcessInitializers(format!("{:?}", ty), *span));
+                }
+                for item in items {
+                    self.check_initializer(name, elem, item, global)?;
+                }
+                Ok(())
+            }
+            (Type::Array(elem, len), Expr::StringLiteral { value, span }) if *elem.unqualified() == Type::Char => {
+                // The terminating NUL is dropped when it exactly does not fit.
+                if value.len() > *len {
+                    return Err(SemanticError::ExcessInitializers(format!("{:?}", ty), *span));
+                }
+                Ok(())
+            }
+            (Type::Struct(tag), Expr::InitList { items, span }) => {
+                let members = self.symbols.struct_members(tag).unwrap().to_vec();
+                if items.len() > members.len() {
+                    return Err(SemanticError::ExcessInitializers(format!("{:?}", ty), *span));
+                }
+                for (item, (_, member_ty)) in items.iter().zip(&members) {
+                    self.check_initializer(name, member_ty, item, global)?;
+                }
+                Ok(())
+            }
+            // A union is initialized through its first member.
+            (Type::Union(tag), Expr::InitList { items, span }) => {
+                let members = self.symbols.struct_members(tag).unwrap().to_vec();
+                if items.len() > 1 {
+                    return Err(SemanticError::ExcessInitializers(format!("{:?}", ty), *span));
+                }
+                if let (Some(item), Some((_, member_ty))) = (items.first(), members.first()) {
+                    self.check_initializer(name, member_ty, item, global)?;
+                }
+                Ok(())
+            }
+            (Type::Array(..), _) | (_, Expr::InitList { .. }) => {
+                Err(SemanticError::InvalidInitializer(format!("{:?}", ty), init.span()))
+            }
+            _ => {
+                let init_ty = self.check_expr(init)?;
+                expect_convertible(&init_ty, ty, init, init.span())?;
+                if global && eval_const_float(init, &mut |e| self.sizeof_value(e)).is_none() && !self.is_address_constant(init) {
+                    return Err(SemanticError::NonConstantInitializer(name.to_string(), init.span()));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether `expr` is a pointer fixed before the program runs: the address of
+    /// an object with static storage or of a function, possibly offset by an
+    /// integer constant, or an integer constant converted to a pointer.
+    fn is_address_constant(&mut self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Unary { op: TokenKind::Ampersand, operand, .. } => self.is_static_lvalue(operand),
+            Expr::Cast { ty, expr, .. } if ty.is_pointer() => {
+                self.is_address_constant(expr) || eval_const(expr, &mut |e| self.sizeof_value(e)).is_some()
+            }
+            Expr::Binary { op: op @ (TokenKind::Plus | TokenKind::Minus), left, right, .. } => {
+                (self.is_address_constant(left) && self.is_integer_constant(right))
+                    || (*op == TokenKind::Plus && self.is_integer_constant(left) && self.is_address_constant(right))
+            }
+            // Arrays and functions decay to their addresses.
+            _ => {
+                matches!(self.check_undecayed(expr), Ok(Type::Array(..) | Type::Function { .. })) && self.is_static_lvalue(expr)
+            }
+        }
+    }
+
+    /// Whether `expr` designates an object with static storage, at an address
+    /// known before the program runs.
+    fn is_static_lvalue(&mut self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Var { name, .. } => self.symbols.lookup(name).is_some_and(|sym| sym.is_static),
+            Expr::StringLiteral { .. } => true,
+            Expr::Index { base, index, .. } => {
+                (self.is_address_constant(base) && self.is_integer_constant(index))
+                    || (self.is_integer_constant(base) && self.is_address_constant(index))
+            }
+            Expr::Member { base, arrow: false, .. } => self.is_static_lvalue(base),
+            Expr::Member { base, arrow: true, .. } | Expr::Unary { op: TokenKind::Star, operand: base, .. } => self.is_address_constant(base),
+            _ => false,
+        }
+    }
+
+    fn is_integer_constant(&mut self, expr: &Expr) -> bool {
+        eval_const(expr, &mut |e| self.sizeof_value(e)).is_some()
+    }
+
+    /// Folds a `sizeof` in a constant expression, using the symbol table's layout.
+    fn sizeof_value(&mut self, sizeof: &Expr) -> Option<u64> {
+        match sizeof {
+            Expr::SizeofType { ty, .. } => self.symbols.size_of(ty),
+            Expr::SizeofExpr { expr, .. } => {
+                let ty = self.check_undecayed(expr).ok()?;
+                self.symbols.size_of(&ty)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_complete(&self, ty: &Type, span: Span) -> Result<(), SemanticError> {
+        if self.symbols.is_complete(ty) {
+            Ok(())
+        } else {
+            Err(SemanticError::IncompleteType(format!("{:?}", ty), span))
+        }
+    }
+
+    fn check_lvalue(&self, expr: &Expr) -> Result<(), SemanticError> {
+        match expr {
+            Expr::Var { .. } | Expr::Unary { op: TokenKind::Star, .. } | Expr::Index { .. } => Ok(()),
+            Expr::Member { arrow: true, .. } => Ok(()),
+            // `s.x` is assignable when `s` is; a member of a returned struct is not.
+            Expr::Member { base, arrow: false, .. } => self.check_lvalue(base),
+            _ => Err(SemanticError::NotAnLvalue(expr.span())),
+        }
+    }
+
+    /// Checks that `expr` may be assigned to, returning its unqualified type.
+    /// Arrays are lvalues but cannot be assigned as a whole, and neither can
+    /// `const` objects.
+    fn check_modifiable(&mut self, expr: &Expr) -> Result<Type, SemanticError> {
+        self.check_lvalue(expr)?;
+        let ty = self.check_undecayed(expr)?;
+        if ty.is_array() || matches!(ty, Type::Function { .. }) {
+            return Err(SemanticError::NotAnLvalue(expr.span()));
+        }
+        if self.is_read_only(&ty) {
+            return Err(SemanticError::ReadOnly(expr.span()));
+        }
+        Ok(ty.unqualified().clone())
+    }
+
+    /// Whether objects of type `ty` are `const`, or are structs or unions with a
+    /// `const` member somewhere inside, which cannot be assigned as a whole either.
+    fn is_read_only(&self, ty: &Type) -> bool {
+        ty.qualifiers().is_const
+            || ty.tag()
+                .and_then(|tag| self.symbols.struct_members(tag))
+                .is_some_and(|members| members.iter().any(|(_, member_ty)| self.is_read_only(member_ty)))
+    }
+
+    /// Type of `expr` used as a value, after arrays decay to pointers.
     fn check_expr(&mut self, expr: &Expr) -> Result<Type, SemanticError> {
+        Ok(self.check_undecayed(expr)?.decay())
+    }
+
+    /// Type of `expr` itself, before array-to-pointer decay, as `&` sees it.
+    #[instrument(level = "debug", skip(self, expr))]
+    fn check_undecayed(&mut self, expr: &Expr) -> Result<Type, SemanticError> {
         match expr {
-            Expr::IntLiteral { .. } => Ok(Type::Int),
-            Expr::Var { name, span } => {
-                if let Some(sym) = self.symbols.lookup(name) {
-                    Ok(sym.ty.clone())
+            Expr::IntLiteral { ty, .. } | Expr::FloatLiteral { ty, .. } => Ok(ty.clone()),
+            // A string literal is an array holding its bytes and a NUL, which
+            // decays like any other array except under `sizeof`.
+            Expr::StringLiteral { value, .. } => Ok(Type::Array(Box::new(Type::Char), value.len() + 1)),
+            // Typedef names and enumeration constants never reach here as variables:
+            // the parser resolves those in scope, so any other use is undefined.
+            Expr::Var { name, span } => match self.symbols.lookup(name) {
+                Some(sym) if sym.kind == SymbolKind::Object => Ok(sym.ty.clone()),
+                _ => Err(SemanticError::UndefinedVariable(name.clone(), *span)),
+            },
+            Expr::Unary { op: TokenKind::PlusPlus | TokenKind::MinusMinus, operand, span }
+            | Expr::Postfix { operand, span, .. } => {
+                let ty = self.check_modifiable(operand)?;
+                if !is_object_pointer(&ty) {
+                    expect_arithmetic(&ty, *span)?;
+                }
+                Ok(ty)
+            }
+            Expr::Unary { op: TokenKind::Ampersand, operand, .. } => {
+                self.check_lvalue(operand)?;
+                Ok(Type::Pointer(Box::new(self.check_undecayed(operand)?)))
+            }
+            Expr::Unary { op: TokenKind::Star, operand, span } => {
+                let ty = self.check_expr(operand)?;
+                match ty.pointee() {
+                    Some(pointee) if is_object_pointer(&ty) => Ok(pointee.clone()),
+                    _ => Err(SemanticError::TypeMismatch { expected: "object pointer".into(), found: format!("{:?}", ty), span: *span }),
+                }
+            }
+            Expr::Unary { op: TokenKind::LogicalNot, operand, span } => {
+                let ty = self.check_expr(operand)?;
+                expect_scalar(&ty, *span)?;
+                Ok(Type::Int)
+            }
+            Expr::Unary { op, operand, span } => {
+                let ty = self.check_expr(operand)?;
+                if *op == TokenKind::Tilde {
+                    expect_integer(&ty, *span)?;
                 } else {
-                    Err(SemanticError::UndefinedVariable(name.clone(), *span))
+                    expect_arithmetic(&ty, *span)?;
                 }
+                Ok(ty.promote())
             }
-            Expr::Binary { left, right, span, .. } => {
-                let lt = self.check_expr(left)?;
+            Expr::Binary { op, left, right, span } => {
+                let lt = if op.is_assignment() { self.check_modifiable(left)? } else { self.check_expr(left)? };
                 let rt = self.check_expr(right)?;
-                if lt != Type::Int || rt != Type::Int {
+                if *op == TokenKind::Assign {
+                    expect_convertible(&rt, &lt, right, *span)?;
+                } else if lt.is_pointer() || rt.is_pointer() {
+                    let arith_op = op.compound_op().unwrap_or(op.clone());
+                    if !pointer_operands_valid(&arith_op, (&lt, left), (&rt, right)) {
+                        return Err(SemanticError::InvalidOperands {
+                            op: format!("{:?}", arith_op),
+                            lhs: format!("{:?}", lt),
+                            rhs: format!("{:?}", rt),
+                            span: *span,
+                        });
+                    }
+                    // `p += n` and `p -= n` must leave a pointer in `p`.
+                    if op.is_assignment() {
+                        expect_integer(&rt, *span)?;
+                    }
+                } else {
+                    // `%`, the bitwise operators and shifts need integers; the rest
+                    // take any arithmetic operands, and `&&`/`||` any scalars.
+                    let check = match op.compound_op().unwrap_or(op.clone()) {
+                        TokenKind::LogicalAnd | TokenKind::LogicalOr => expect_scalar,
+                        TokenKind::Percent
+                        | TokenKind::Ampersand
+                        | TokenKind::Pipe
+                        | TokenKind::Caret
+                        | TokenKind::ShiftLeft
+                        | TokenKind::ShiftRight => expect_integer,
+                        _ => expect_arithmetic,
+                    };
+                    check(&lt, *span)?;
+                    check(&rt, *span)?;
+                }
+                Ok(Type::binary_result(op, &lt, &rt))
+            }
+            Expr::Conditional { cond, then_expr, else_expr, span } => {
+                let cond_ty = self.check_expr(cond)?;
+                expect_scalar(&cond_ty, cond.span())?;
+                let then_ty = self.check_expr(then_expr)?;
+                let else_ty = self.check_expr(else_expr)?;
+                if then_ty.is_arithmetic() && else_ty.is_arithmetic() {
+                    return Ok(Type::arithmetic(&then_ty, &else_ty));
+                }
+                if then_ty.is_pointer() && is_null_pointer(else_expr) {
+                    return Ok(then_ty);
+                }
+                if else_ty.is_pointer() && is_null_pointer(then_expr) {
+                    return Ok(else_ty);
+                }
+                if then_ty != else_ty {
                     return Err(SemanticError::TypeMismatch {
-                        expected: "Int".into(),
-                        found: format!("{:?}", if lt != Type::Int { lt } else { rt }),
+                        expected: format!("{:?}", then_ty),
+                        found: format!("{:?}", else_ty),
                         span: *span,
                     });
                 }
-                Ok(Type::Int)
+                Ok(then_ty)
+            }
+            Expr::Call { callee, args, span } => {
+                let (ret, params, variadic) = match self.symbols.lookup(callee) {
+                    Some(sym) => match &sym.ty {
+                        Type::Function { ret, params, variadic } => ((**ret).clone(), params.clone(), *variadic),
+                        _ => return Err(SemanticError::NotAFunction(callee.clone(), *span)),
+                    },
+                    None => return Err(SemanticError::UndefinedFunction(callee.clone(), *span)),
+                };
+                if args.len() < params.len() || (args.len() > params.len() && !variadic) {
+                    return Err(SemanticError::ArgumentCount {
+                        name: callee.clone(),
+                        expected: params.len(),
+                        found: args.len(),
+                        span: *span,
+                    });
+                }
+                for (arg, param_ty) in args.iter().zip(&params) {
+                    let ty = self.check_expr(arg)?;
+                    expect_convertible(&ty, param_ty, arg, arg.span())?;
+                }
+                for arg in &args[params.len()..] {
+                    let ty = self.check_expr(arg)?;
+                    if ty == Type::Void {
+                        return Err(SemanticError::TypeMismatch { expected: "value".into(), found: "Void".into(), span: arg.span() });
+                    }
+                }
+                Ok(ret)
+            }
+            Expr::Index { base, index, .. } => {
+                let base_ty = self.check_expr(base)?;
+                let index_ty = self.check_expr(index)?;
+                // `i[a]` is as valid as `a[i]`.
+                let (ptr_ty, int_ty, int_span) = if base_ty.is_pointer() {
+                    (base_ty, index_ty, index.span())
+                } else {
+                    (index_ty, base_ty, base.span())
+                };
+                expect_integer(&int_ty, int_span)?;
+                if !is_object_pointer(&ptr_ty) {
+                    return Err(SemanticError::TypeMismatch { expected: "pointer".into(), found: format!("{:?}", ptr_ty), span: base.span() });
+                }
+                Ok(ptr_ty.pointee().unwrap().clone())
+            }
+            Expr::Member { base, member, arrow, span } => {
+                let base_ty = if *arrow { self.check_expr(base)? } else { self.check_undecayed(base)? };
+                let struct_ty = match (&base_ty, *arrow) {
+                    (Type::Pointer(pointee), true) => &**pointee,
+                    (ty, false) => ty,
+                    _ => return Err(SemanticError::TypeMismatch { expected: "pointer".into(), found: format!("{:?}", base_ty), span: base.span() }),
+                };
+                let Some(tag) = struct_ty.tag() else {
+                    return Err(SemanticError::TypeMismatch { expected: "struct or union".into(), found: format!("{:?}", struct_ty), span: base.span() });
+                };
+                let members = self.symbols.struct_members(tag)
+                    .ok_or_else(|| SemanticError::IncompleteType(format!("{:?}", struct_ty), base.span()))?;
+                // A member of a const or volatile object is itself const or volatile.
+                members.iter()
+                    .find(|(name, _)| name == member)
+                    .map(|(_, ty)| ty.clone().qualified(struct_ty.qualifiers()))
+                    .ok_or_else(|| SemanticError::NoSuchMember { ty: format!("{:?}", struct_ty), member: member.clone(), span: *span })
+            }
+            Expr::Cast { ty, expr, span } => {
+                let from = self.check_expr(expr)?;
+                // Anything may be cast to `void` to discard it. Otherwise both types
+                // are scalars, and pointers convert only to and from integers.
+                let ok = *ty == Type::Void
+                    || (from.is_arithmetic() && ty.is_arithmetic())
+                    || (from.is_pointer() && (ty.is_pointer() || ty.is_integer()))
+                    || (from.is_integer() && ty.is_pointer());
+                if !ok {
+                    return Err(SemanticError::InvalidCast { from: format!("{:?}", from), to: format!("{:?}", ty), span: *span });
+                }
+                Ok(ty.clone())
+            }
+            Expr::SizeofType { ty, span } => {
+                self.expect_complete(ty, *span)?;
+                Ok(Type::ULong)
+            }
+            Expr::SizeofExpr { expr, span } => {
+                let ty = self.check_undecayed(expr)?;
+                self.expect_complete(&ty, *span)?;
+                Ok(Type::ULong)
+            }
+            Expr::InitList { span, .. } => {
+                Err(SemanticError::TypeMismatch { expected: "expression".into(), found: "initializer list".into(), span: *span })
             }
         }
     }
 }
+
+fn expect_integer(ty: &Type, span: Span) -> Result<(), SemanticError> {
+    if ty.is_integer() {
+        Ok(())
+    } else {
+        Err(SemanticError::TypeMismatch { expected: "integer".into(), found: format!("{:?}", ty), span })
+    }
+}
+
+fn expect_arithmetic(ty: &Type, span: Span) -> Result<(), SemanticError> {
+    if ty.is_arithmetic() {
+        Ok(())
+    } else {
+        Err(SemanticError::TypeMismatch { expected: "arithmetic type".into(), found: format!("{:?}", ty), span })
+    }
+}
+
+fn expect_scalar(ty: &Type, span: Span) -> Result<(), SemanticError> {
+    if ty.is_scalar() {
+        Ok(())
+    } else {
+        Err(SemanticError::TypeMismatch { expected: "scalar".into(), found: format!("{:?}", ty), span })
+    }
+}
+
+/// A pointer to a complete object type, which arithmetic can step over.
+/// Functions are called by name only, so a pointer to one is not dereferenced.
+fn is_object_pointer(ty: &Type) -> bool {
+    ty.pointee().is_some_and(|pointee| !matches!(pointee.unqualified(), Type::Void | Type::Function { .. }))
+}
+
+/// An integer constant expression with value 0, which converts to any pointer type.
+fn is_null_pointer(expr: &Expr) -> bool {
+    eval_const(expr, &mut |_| None) == Some(0)
+}
+
+/// Pointers to the same type are compatible, whatever the qualifiers of the
+/// pointed-to types, and `void *` is compatible with any object pointer.
+fn pointers_compatible(lhs: &Type, rhs: &Type) -> bool {
+    match (lhs.pointee().map(Type::unqualified), rhs.pointee().map(Type::unqualified)) {
+        (Some(l), Some(r)) => l == r || *l == Type::Void || *r == Type::Void,
+        _ => false,
+    }
+}
+
+/// Pointers to the same type, ignoring the qualifiers of the pointed-to types,
+/// which may be subtracted and ordered.
+fn same_pointee(lhs: &Type, rhs: &Type) -> bool {
+    lhs.pointee().map(Type::unqualified) == rhs.pointee().map(Type::unqualified)
+}
+
+/// Checks a binary operator with at least one pointer operand: pointer
+/// arithmetic, pointer comparisons and logical operators.
+fn pointer_operands_valid(op: &TokenKind, (lt, left): (&Type, &Expr), (rt, right): (&Type, &Expr)) -> bool {
+    match op {
+        TokenKind::Plus => (is_object_pointer(lt) && rt.is_integer()) || (lt.is_integer() && is_object_pointer(rt)),
+        TokenKind::Minus => is_object_pointer(lt) && (rt.is_integer() || same_pointee(lt, rt)),
+        TokenKind::Equal | TokenKind::NotEqual => {
+            pointers_compatible(lt, rt) || (lt.is_pointer() && is_null_pointer(right)) || (rt.is_pointer() && is_null_pointer(left))
+        }
+        TokenKind::Less | TokenKind::LessEqual | TokenKind::Greater | TokenKind::GreaterEqual => {
+            lt.is_pointer() && same_pointee(lt, rt)
+        }
+        TokenKind::LogicalAnd | TokenKind::LogicalOr => lt.is_scalar() && rt.is_scalar(),
+        _ => false,
+    }
+}
+
+/// Checks that `expr`, of type `from`, may be assigned to, passed as or returned
+/// as `to`. Besides arithmetic conversions, `void *` converts to and from any
+/// object pointer and a null pointer constant converts to any pointer. A pointer
+/// conversion may add qualifiers to the pointed-to type but not drop them.
+fn expect_convertible(from: &Type, to: &Type, expr: &Expr, span: Span) -> Result<(), SemanticError> {
+    let to = to.unqualified();
+    if let (Some(from_pointee), Some(to_pointee)) = (from.pointee(), to.pointee()) {
+        if pointers_compatible(from, to) && !to_pointee.qualifiers().contains(from_pointee.qualifiers()) {
+            return Err(SemanticError::DiscardsQualifiers { from: format!("{:?}", from), to: format!("{:?}", to), span });
+        }
+    }
+    let ok = from == to
+        || (from.is_arithmetic() && to.is_arithmetic())
+        || (from.is_pointer() && to.is_pointer() && pointers_compatible(from, to))
+        || (to.is_pointer() && from.is_integer() && is_null_pointer(expr));
+    if ok {
+        Ok(())
+    } else {
+        Err(SemanticError::TypeMismatch { expected: format!("{:?}", to), found: format!("{:?}", from), span })
+    }
+}
@@ -0,0 +1,199 @@
+use crate::ast::nodes::Expr;
+use crate::lexer::TokenKind;
+use crate::semantic::symbol_table::Type;
+
+/// Folds a `sizeof` expression to the size of its operand in bytes, or `None`
+/// if that is not known.
+pub type Sizeof<'a> = dyn FnMut(&Expr) -> Option<u64> + 'a;
+
+/// Evaluates an integer constant expression, returning `None` when `expr`
+/// depends on anything that is not known at compile time. The value is that of
+/// the expression's own type, so `-1u` is 4294967295; a 64-bit unsigned value
+/// is returned as its bit pattern.
+///
+/// `sizeof` gives the size in bytes of the operand of a `sizeof` expression,
+/// which depends on the target and on the types of the names in scope.
+pub fn eval_const(expr: &Expr, sizeof: &mut Sizeof) -> Option<i64> {
+    eval_typed(expr, sizeof).map(|(value, _)| value)
+}
+
+/// Evaluates an integer constant expression along with its type, folding each
+/// operator in the type C gives it: operands undergo the usual arithmetic
+/// conversions, and results wrap to the width of their type.
+fn eval_typed(expr: &Expr, sizeof: &mut Sizeof) -> Option<(i64, Type)> {
+    match expr {
+        Expr::IntLiteral { value, ty, .. } => Some((wrap(*value, ty), ty.clone())),
+        Expr::SizeofType { .. } | Expr::SizeofExpr { .. } => Some((sizeof(expr)? as i64, Type::ULong)),
+        // A cast to an integer truncates a floating operand toward zero, then
+        // wraps to the width of the target type.
+        Expr::Cast { ty, expr, .. } if ty.is_integer() || ty.is_pointer() => {
+            let value = eval_typed(expr, sizeof).map(|(v, _)| v).or_else(|| eval_const_float(expr, sizeof).map(|v| v as i64))?;
+            Some((wrap(value, ty), ty.clone()))
+        }
+        Expr::Unary { op: TokenKind::LogicalNot, operand, .. } => {
+            let (v, _) = eval_typed(operand, sizeof)?;
+            Some(((v == 0) as i64, Type::Int))
+        }
+        Expr::Unary { op, operand, .. } => {
+            let (v, ty) = eval_integer(operand, sizeof)?;
+            let ty = ty.promote();
+            let v = match op {
+                TokenKind::Minus => v.wrapping_neg(),
+                TokenKind::Plus => v,
+                TokenKind::Tilde => !v,
+                _ => return None,
+            };
+            Some((wrap(v, &ty), ty))
+        }
+        // The right operand is only evaluated when the left does not decide the
+        // result, so `1 || 1 / 0` is 1; it must still be a constant expression.
+        Expr::Binary { op: op @ (TokenKind::LogicalAnd | TokenKind::LogicalOr), left, right, .. } => {
+            let (l, _) = eval_typed(left, sizeof)?;
+            if (l != 0) == (*op == TokenKind::LogicalOr) {
+                const_type(right)?;
+                return Some(((l != 0) as i64, Type::Int));
+            }
+            let (r, _) = eval_typed(right, sizeof)?;
+            Some(((r != 0) as i64, Type::Int))
+        }
+        Expr::Binary { op: op @ (TokenKind::ShiftLeft | TokenKind::ShiftRight), left, right, .. } => {
+            let (l, lt) = eval_integer(left, sizeof)?;
+            let (r, _) = eval_integer(right, sizeof)?;
+            let ty = lt.promote();
+            let count = u32::try_from(r).ok().filter(|&count| count < ty.int_bits())?;
+            let v = match op {
+                TokenKind::ShiftLeft => l.wrapping_shl(count),
+                _ if ty.is_unsigned() => ((l as u64) >> count) as i64,
+                _ => l >> count,
+            };
+            Some((wrap(v, &ty), ty))
+        }
+        Expr::Binary { op, left, right, .. } => {
+            let (l, lt) = eval_integer(left, sizeof)?;
+            let (r, rt) = eval_integer(right, sizeof)?;
+            let ty = Type::arithmetic(&lt, &rt);
+            let (l, r) = (wrap(l, &ty), wrap(r, &ty));
+            let unsigned = ty.is_unsigned();
+            let v = match op {
+                TokenKind::Plus => l.wrapping_add(r),
+                TokenKind::Minus => l.wrapping_sub(r),
+                TokenKind::Star => l.wrapping_mul(r),
+                TokenKind::Slash if unsigned => (l as u64).checked_div(r as u64)? as i64,
+                TokenKind::Slash => l.checked_div(r)?,
+                TokenKind::Percent if unsigned => (l as u64).checked_rem(r as u64)? as i64,
+                TokenKind::Percent => l.checked_rem(r)?,
+                TokenKind::Ampersand => l & r,
+                TokenKind::Pipe => l | r,
+                TokenKind::Caret => l ^ r,
+                TokenKind::Less | TokenKind::LessEqual | TokenKind::Greater | TokenKind::GreaterEqual => {
+                    let ordering = if unsigned { (l as u64).cmp(&(r as u64)) } else { l.cmp(&r) };
+                    let v = match op {
+                        TokenKind::Less => ordering.is_lt(),
+                        TokenKind::LessEqual => ordering.is_le(),
+                        TokenKind::Greater => ordering.is_gt(),
+                        _ => ordering.is_ge(),
+                    };
+                    return Some((v as i64, Type::Int));
+                }
+                TokenKind::Equal => return Some(((l == r) as i64, Type::Int)),
+                TokenKind::NotEqual => return Some(((l != r) as i64, Type::Int)),
+                _ => return None,
+            };
+            Some((wrap(v, &ty), ty))
+        }
+        Expr::Conditional { cond, then_expr, else_expr, .. } => {
+            let (c, _) = eval_typed(cond, sizeof)?;
+            let ty = const_type(expr).filter(Type::is_integer)?;
+            let (v, _) = eval_integer(if c != 0 { then_expr } else { else_expr }, sizeof)?;
+            Some((wrap(v, &ty), ty))
+        }
+        _ => None,
+    }
+}
+
+/// Type of the arithmetic constant expression `expr`, found without evaluating
+/// it, as for an operand that `&&`, `||` or `?:` skips.
+fn const_type(expr: &Expr) -> Option<Type> {
+    match expr {
+        Expr::IntLiteral { ty, .. } | Expr::FloatLiteral { ty, .. } => Some(ty.clone()),
+        Expr::Cast { ty, expr, .. } if ty.is_arithmetic() => {
+            const_type(expr)?;
+            Some(ty.clone())
+        }
+        Expr::SizeofType { .. } | Expr::SizeofExpr { .. } => Some(Type::ULong),
+        Expr::Unary { op: TokenKind::LogicalNot, operand, .. } => {
+            const_type(operand)?;
+            Some(Type::Int)
+        }
+        Expr::Unary { op: TokenKind::Minus | TokenKind::Plus | TokenKind::Tilde, operand, .. } => Some(const_type(operand)?.promote()),
+        Expr::Binary { op, left, right, .. } => {
+            let (lt, rt) = (const_type(left)?, const_type(right)?);
+            if !lt.is_arithmetic() || !rt.is_arithmetic() {
+                return None;
+            }
+            match op {
+                TokenKind::ShiftLeft | TokenKind::ShiftRight => Some(lt.promote()),
+                TokenKind::Plus | TokenKind::Minus | TokenKind::Star | TokenKind::Slash | TokenKind::Percent
+                | TokenKind::Ampersand | TokenKind::Pipe | TokenKind::Caret => Some(Type::arithmetic(&lt, &rt)),
+                _ => Some(Type::Int),
+            }
+        }
+        Expr::Conditional { cond, then_expr, else_expr, .. } => {
+            const_type(cond)?;
+            Some(Type::arithmetic(&const_type(then_expr)?, &const_type(else_expr)?))
+        }
+        _ => None,
+    }
+}
+
+/// `eval_typed` restricted to constants of integer type, which the arithmetic
+/// operators work on.
+fn eval_integer(expr: &Expr, sizeof: &mut Sizeof) -> Option<(i64, Type)> {
+    eval_typed(expr, sizeof).filter(|(_, ty)| ty.is_integer())
+}
+
+/// Reduces `value` to the range of the integer type `ty`, as a conversion to
+/// `ty` does: the low `int_bits` bits are kept and sign-extended if `ty` is signed.
+pub fn wrap(value: i64, ty: &Type) -> i64 {
+    if !ty.is_integer() || ty.int_bits() == 64 {
+        return value;
+    }
+    let unused = 64 - ty.int_bits();
+    if ty.is_unsigned() {
+        ((value as u64) << unused >> unused) as i64
+    } else {
+        value << unused >> unused
+    }
+}
+
+/// Evaluates an arithmetic constant expression that may involve floating
+/// constants, as used to initialize floating-point globals. Integer
+/// subexpressions keep integer semantics, so `1 / 2` is still 0.
+pub fn eval_const_float(expr: &Expr, sizeof: &mut Sizeof) -> Option<f64> {
+    if let Some((v, ty)) = eval_typed(expr, sizeof) {
+        return Some(if ty.is_unsigned() { v as u64 as f64 } else { v as f64 });
+    }
+    match expr {
+        Expr::FloatLiteral { value, .. } => Some(*value),
+        Expr::Cast { ty: Type::Float | Type::Double, expr, .. } => eval_const_float(expr, sizeof),
+        Expr::Unary { op: TokenKind::Minus, operand, .. } => Some(-eval_const_float(operand, sizeof)?),
+        Expr::Unary { op: TokenKind::Plus, operand, .. } => eval_const_float(operand, sizeof),
+        Expr::Conditional { cond, then_expr, else_expr, .. } => {
+            const_type(expr)?;
+            let c = eval_const_float(cond, sizeof)?;
+            eval_const_float(if c != 0.0 { then_expr } else { else_expr }, sizeof)
+        }
+        Expr::Binary { op, left, right, .. } => {
+            let l = eval_const_float(left, sizeof)?;
+            let r = eval_const_float(right, sizeof)?;
+            match op {
+                TokenKind::Plus => Some(l + r),
+                TokenKind::Minus => Some(l - r),
+                TokenKind::Star => Some(l * r),
+                TokenKind::Slash => Some(l / r),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -5,10 +5,77 @@ use crate::utils::span::Span;
 pub enum SemanticError {
     #[error("Duplicate symbol '{0}' at {1:?}")]
     DuplicateSymbol(String, Span),
+    #[error("Conflicting types for '{0}' at {1:?}")]
+    ConflictingTypes(String, Span),
+    #[error("Conflicting linkage for '{0}' at {1:?}")]
+    ConflictingLinkage(String, Span),
 
     #[error("Undefined variable '{0}' at {1:?}")]
     UndefinedVariable(String, Span),
 
+    #[error("Undefined function '{0}' at {1:?}")]
+    UndefinedFunction(String, Span),
+
+    #[error("'{0}' is not a function at {1:?}")]
+    NotAFunction(String, Span),
+
+    #[error("Function '{name}' expects {expected} argument(s), found {found} at {span:?}")]
+    ArgumentCount { name: String, expected: usize, found: usize, span: Span },
+
+    #[error("Initializer of global '{0}' is not a constant expression at {1:?}")]
+    NonConstantInitializer(String, Span),
+
+    #[error("Function '{0}' returns void but a value is returned at {1:?}")]
+    ReturnValueInVoid(String, Span),
+
+    #[error("Function '{0}' must return a value at {1:?}")]
+    MissingReturnValue(String, Span),
+
+    #[error("'break' outside of a loop or switch at {0:?}")]
+    BreakOutsideLoop(Span),
+
+    #[error("'continue' outside of a loop at {0:?}")]
+    ContinueOutsideLoop(Span),
+
+    #[error("'{0}' label not directly inside a switch body at {1:?}")]
+    LabelOutsideSwitch(String, Span),
+
+    #[error("Case label is not an integer constant expression at {0:?}")]
+    NonConstantCase(Span),
+
+    #[error("Duplicate case value {0} at {1:?}")]
+    DuplicateCase(i64, Span),
+
+    #[error("Multiple default labels in one switch at {0:?}")]
+    DuplicateDefault(Span),
+
+    #[error("Expression is not assignable at {0:?}")]
+    NotAnLvalue(Span),
+
+    #[error("Cannot modify read-only location at {0:?}")]
+    ReadOnly(Span),
+
+    #[error("Conversion from {from} to {to} discards qualifiers at {span:?}")]
+    DiscardsQualifiers { from: String, to: String, span: Span },
+
+    #[error("Incomplete type {0} at {1:?}")]
+    IncompleteType(String, Span),
+
+    #[error("{ty} has no member named '{member}' at {span:?}")]
+    NoSuchMember { ty: String, member: String, span: Span },
+
+    #[error("Invalid initializer for {0} at {1:?}")]
+    InvalidInitializer(String, Span),
+
+    #[error("Excess elements in initializer for {0} at {1:?}")]
+    ExcessInitializers(String, Span),
+
+    #[error("Invalid operands to {op} ({lhs} and {rhs}) at {span:?}")]
+    InvalidOperands { op: String, lhs: String, rhs: String, span: Span },
+
+    #[error("Cannot cast {from} to {to} at {span:?}")]
+    InvalidCast { from: String, to: String, span: Span },
+
     #[error("Type mismatch: expected {expected:?}, found {found:?} at {span:?}")]
     TypeMismatch { expected: String, found: String, span: Span },
 }
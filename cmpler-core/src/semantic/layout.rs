@@ -0,0 +1,102 @@
+use inkwell::context::Context;
+use inkwell::targets::TargetData;
+use inkwell::types::AnyType;
+use inkwell::AddressSpace;
+use crate::semantic::symbol_table::Type;
+
+/// Sizes and ABI alignments of the scalar types on the machine being compiled
+/// for, read from its LLVM data layout so that `sizeof` folds to what codegen
+/// emits. `long` is as wide as a pointer, as on the LP64 and ILP32 targets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetLayout {
+    pointer: (u64, u64),
+    float: (u64, u64),
+    double: (u64, u64),
+    /// Integers of 8, 16, 32 and 64 bits.
+    integers: [(u64, u64); 4],
+    long_bits: u32,
+}
+
+impl TargetLayout {
+    pub fn new(target_data: &TargetData) -> Self {
+        let context = Context::create();
+        let scalar = |ty: &dyn AnyType| (target_data.get_abi_size(ty), u64::from(target_data.get_abi_alignment(ty)));
+        TargetLayout {
+            pointer: scalar(&context.ptr_type(AddressSpace::default())),
+            float: scalar(&context.f32_type()),
+            double: scalar(&context.f64_type()),
+            integers: [8, 16, 32, 64].map(|bits| scalar(&context.custom_width_int_type(bits))),
+            long_bits: target_data.get_pointer_byte_size(None) * 8,
+        }
+    }
+
+    /// Width of the integer type `ty` in bits when stored.
+    pub fn int_bits(&self, ty: &Type) -> u32 {
+        match ty.unqualified() {
+            Type::Long | Type::ULong => self.long_bits,
+            integer => integer.int_bits(),
+        }
+    }
+
+    /// Size and alignment of the scalar type `ty`.
+    fn scalar(&self, ty: &Type) -> (u64, u64) {
+        match ty.unqualified() {
+            Type::Pointer(_) => self.pointer,
+            Type::Float => self.float,
+            Type::Double => self.double,
+            integer => self.integers[(self.int_bits(integer) / 8).trailing_zeros() as usize],
+        }
+    }
+}
+
+/// Sizes and alignments of types as `sizeof` sees them, so that it can be folded
+/// before code generation. Scalars are laid out as the target's data layout
+/// says; members are laid out in order, each at the next multiple of its
+/// alignment, as LLVM lays out the structs codegen emits.
+pub trait Layout {
+    /// Members of the struct or union with tag `tag`, if it has been defined.
+    fn members(&self, tag: &str) -> Option<&[(String, Type)]>;
+
+    /// Layout of the scalar types on the target.
+    fn target(&self) -> &TargetLayout;
+
+    /// Size of `ty` in bytes, or `None` if it is incomplete.
+    fn size_of(&self, ty: &Type) -> Option<u64> {
+        match ty {
+            Type::Array(elem, len) => Some(self.size_of(elem)? * *len as u64),
+            Type::Struct(tag) => {
+                let mut size: u64 = 0;
+                for (_, member) in self.members(tag)? {
+                    size = size.next_multiple_of(self.align_of(member)?) + self.size_of(member)?;
+                }
+                Some(size.next_multiple_of(self.align_of(ty)?))
+            }
+            Type::Union(tag) => {
+                let mut size = 0;
+                for (_, member) in self.members(tag)? {
+                    size = size.max(self.size_of(member)?);
+                }
+                Some(size.next_multiple_of(self.align_of(ty)?))
+            }
+            Type::Qualified(inner, _) => self.size_of(inner),
+            Type::Void | Type::Function { .. } => None,
+            scalar => Some(self.target().scalar(scalar).0),
+        }
+    }
+
+    /// Alignment of `ty` in bytes, or `None` if it is incomplete.
+    fn align_of(&self, ty: &Type) -> Option<u64> {
+        match ty {
+            Type::Array(elem, _) | Type::Qualified(elem, _) => self.align_of(elem),
+            Type::Struct(tag) | Type::Union(tag) => {
+                let mut align = 1;
+                for (_, member) in self.members(tag)? {
+                    align = align.max(self.align_of(member)?);
+                }
+                Some(align)
+            }
+            Type::Void | Type::Function { .. } => None,
+            scalar => Some(self.target().scalar(scalar).1),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 pub mod error;
 pub mod symbol_table;
 pub mod analyzer;
+pub mod const_eval;
+pub mod layout;
 
 pub use analyzer::SemanticAnalyzer;
 pub use error::SemanticError;
@@ -1,29 +1,337 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::utils::span::Span;
-use crate::ast::nodes::Decl;
+use crate::ast::nodes::{Decl, StorageClass};
 use crate::semantic::error::SemanticError;
+use crate::semantic::layout::{Layout, TargetLayout};
+use crate::lexer::TokenKind;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
     Int,
+    /// Plain `char`, which is signed.
+    Char,
+    Short,
+    Long,
+    LongLong,
+    UChar,
+    UShort,
+    UInt,
+    ULong,
+    ULongLong,
+    Float,
+    Double,
     Void,
+    Pointer(Box<Type>),
+    /// An array of a fixed number of elements, or of 0 for an `extern` array
+    /// whose size is left to its definition.
+    Array(Box<Type>, usize),
+    /// A structure, referred to by its tag; the members live in the symbol table.
+    Struct(String),
+    /// A union, referred to by its tag like a structure.
+    Union(String),
+    Function {
+        ret: Box<Type>,
+        params: Vec<Type>,
+        variadic: bool,
+    },
+    /// A `const` or `volatile` qualified type. Only objects are qualified: an
+    /// array carries the qualifiers of its elements, and values lose theirs
+    /// (see `decay`).
+    Qualified(Box<Type>, Qualifiers),
+}
+
+/// The `const` and `volatile` qualifiers of a type.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Qualifiers {
+    pub is_const: bool,
+    pub is_volatile: bool,
+}
+
+impl Qualifiers {
+    pub fn is_empty(&self) -> bool {
+        !self.is_const && !self.is_volatile
+    }
+
+    /// Whether these qualifiers include all of `other`.
+    pub fn contains(&self, other: Qualifiers) -> bool {
+        (self.is_const || !other.is_const) && (self.is_volatile || !other.is_volatile)
+    }
+
+    pub fn union(&self, other: Qualifiers) -> Qualifiers {
+        Qualifiers { is_const: self.is_const || other.is_const, is_volatile: self.is_volatile || other.is_volatile }
+    }
+}
+
+impl Type {
+    pub fn is_integer(&self) -> bool {
+        self.is_signed() || self.is_unsigned()
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self.unqualified(), Type::Char | Type::Short | Type::Int | Type::Long | Type::LongLong)
+    }
+
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self.unqualified(), Type::UChar | Type::UShort | Type::UInt | Type::ULong | Type::ULongLong)
+    }
+
+    /// `self` with `qualifiers` added. Qualifying an array qualifies its elements.
+    pub fn qualified(self, qualifiers: Qualifiers) -> Type {
+        if qualifiers.is_empty() {
+            return self;
+        }
+        match self {
+            Type::Array(elem, len) => Type::Array(Box::new(elem.qualified(qualifiers)), len),
+            Type::Qualified(inner, own) => Type::Qualified(inner, own.union(qualifiers)),
+            other => Type::Qualified(Box::new(other), qualifiers),
+        }
+    }
+
+    /// The type without its top-level qualifiers.
+    pub fn unqualified(&self) -> &Type {
+        match self {
+            Type::Qualified(inner, _) => inner,
+            other => other,
+        }
+    }
+
+    /// The top-level qualifiers of the type, which for an array are those of its
+    /// elements.
+    pub fn qualifiers(&self) -> Qualifiers {
+        match self {
+            Type::Qualified(_, qualifiers) => *qualifiers,
+            Type::Array(elem, _) => elem.qualifiers(),
+            _ => Qualifiers::default(),
+        }
+    }
+
+    /// Integer conversion rank: `char` < `short` < `int` < `long` < `long long`.
+    fn rank(&self) -> u8 {
+        match self.unqualified() {
+            Type::Char | Type::UChar => 1,
+            Type::Short | Type::UShort => 2,
+            Type::Int | Type::UInt => 3,
+            Type::Long | Type::ULong => 4,
+            Type::LongLong | Type::ULongLong => 5,
+            _ => unreachable!("{:?} is not an integer type", self),
+        }
+    }
+
+    /// Width of an integer type in bits as constant expressions and the usual
+    /// arithmetic conversions see it, with a 64-bit `long`. How wide it is in
+    /// memory is up to the target (see `TargetLayout::int_bits`).
+    pub fn int_bits(&self) -> u32 {
+        match self.rank() {
+            1 => 8,
+            2 => 16,
+            3 => 32,
+            _ => 64,
+        }
+    }
+
+    /// The unsigned integer type of the same rank.
+    pub fn to_unsigned(&self) -> Type {
+        match self.unqualified() {
+            Type::Char | Type::UChar => Type::UChar,
+            Type::Short | Type::UShort => Type::UShort,
+            Type::Int | Type::UInt => Type::UInt,
+            Type::Long | Type::ULong => Type::ULong,
+            Type::LongLong | Type::ULongLong => Type::ULongLong,
+            _ => unreachable!("{:?} is not an integer type", self),
+        }
+    }
+
+    /// Whether `value`, read as the bit pattern of a `u64`, fits in this integer type.
+    pub fn fits(&self, value: u64) -> bool {
+        let bits = if self.is_unsigned() { self.int_bits() } else { self.int_bits() - 1 };
+        bits == 64 || value < 1 << bits
+    }
+
+    pub fn is_floating(&self) -> bool {
+        matches!(self.unqualified(), Type::Float | Type::Double)
+    }
+
+    /// Integer and floating types, which the arithmetic operators accept.
+    pub fn is_arithmetic(&self) -> bool {
+        self.is_integer() || self.is_floating()
+    }
+
+    pub fn is_pointer(&self) -> bool {
+        matches!(self.unqualified(), Type::Pointer(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Type::Array(..))
+    }
+
+    /// Whether this is the type of a function declared with `()`, whose parameters
+    /// are unspecified. It is called like a variadic function without any fixed
+    /// parameters, which no declaration can otherwise spell.
+    pub fn is_unprototyped(&self) -> bool {
+        matches!(self, Type::Function { params, variadic: true, .. } if params.is_empty())
+    }
+
+    /// The type of an object used as a value: an array decays to a pointer to
+    /// its first element, a function to a pointer to it, and qualifiers are dropped.
+    pub fn decay(self) -> Type {
+        match self {
+            Type::Array(elem, _) => Type::Pointer(elem),
+            Type::Function { .. } => Type::Pointer(Box::new(self)),
+            Type::Qualified(inner, _) => *inner,
+            other => other,
+        }
+    }
+
+    /// Scalar types may be tested for truth and compared against zero.
+    pub fn is_scalar(&self) -> bool {
+        self.is_arithmetic() || self.is_pointer()
+    }
+
+    /// The tag of a struct or union type.
+    pub fn tag(&self) -> Option<&str> {
+        match self.unqualified() {
+            Type::Struct(tag) | Type::Union(tag) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// The type a pointer points to, or `None` for non-pointers.
+    pub fn pointee(&self) -> Option<&Type> {
+        match self.unqualified() {
+            Type::Pointer(inner) => Some(inner),
+            _ => None,
+        }
+    }
+
+    /// Integer promotion: types ranked below `int` are widened to `int`, which
+    /// can represent all of their values.
+    pub fn promote(&self) -> Type {
+        match self {
+            ty if ty.is_integer() && ty.rank() < Type::Int.rank() => Type::Int,
+            other => other.unqualified().clone(),
+        }
+    }
+
+    /// The default argument promotions applied to arguments matching `...`:
+    /// integer promotion, and `float` widened to `double`.
+    pub fn promote_argument(&self) -> Type {
+        match self.unqualified() {
+            Type::Float => Type::Double,
+            other => other.promote(),
+        }
+    }
+
+    /// The usual arithmetic conversions: the common type both operands of a
+    /// binary arithmetic operator are converted to. A floating operand makes
+    /// the result floating. Between a signed and an unsigned operand, the
+    /// unsigned type wins unless the signed type is of higher rank and wide
+    /// enough to hold all its values.
+    pub fn arithmetic(lhs: &Type, rhs: &Type) -> Type {
+        let (lhs, rhs) = (lhs.unqualified(), rhs.unqualified());
+        if *lhs == Type::Double || *rhs == Type::Double {
+            return Type::Double;
+        }
+        if *lhs == Type::Float || *rhs == Type::Float {
+            return Type::Float;
+        }
+        let (lhs, rhs) = (lhs.promote(), rhs.promote());
+        if lhs == rhs {
+            return lhs;
+        }
+        if lhs.is_unsigned() == rhs.is_unsigned() {
+            return if lhs.rank() >= rhs.rank() { lhs } else { rhs };
+        }
+        let (unsigned, signed) = if lhs.is_unsigned() { (lhs, rhs) } else { (rhs, lhs) };
+        if unsigned.rank() >= signed.rank() {
+            unsigned
+        } else if signed.int_bits() > unsigned.int_bits() {
+            signed
+        } else {
+            signed.to_unsigned()
+        }
+    }
+
+    /// The type both operands of a comparison, or both arms of `?:`, are converted
+    /// to. When either is a pointer the other is a null pointer constant or a
+    /// compatible pointer, so the pointer type wins.
+    pub fn common(lhs: &Type, rhs: &Type) -> Type {
+        match (lhs, rhs) {
+            (Type::Pointer(_), _) => lhs.clone(),
+            (_, Type::Pointer(_)) => rhs.clone(),
+            _ => Type::arithmetic(lhs, rhs),
+        }
+    }
+
+    /// Result type of a binary operator applied to operands of these types.
+    /// Shifts take the promoted type of their left operand alone. Adding an
+    /// integer to a pointer yields the pointer type; the difference of two
+    /// pointers is a `long` element count.
+    pub fn binary_result(op: &TokenKind, lhs: &Type, rhs: &Type) -> Type {
+        match op {
+            _ if op.is_assignment() => lhs.clone(),
+            TokenKind::Less
+            | TokenKind::LessEqual
+            | TokenKind::Greater
+            | TokenKind::GreaterEqual
+            | TokenKind::Equal
+            | TokenKind::NotEqual
+            | TokenKind::LogicalAnd
+            | TokenKind::LogicalOr => Type::Int,
+            TokenKind::ShiftLeft | TokenKind::ShiftRight => lhs.promote(),
+            TokenKind::Plus | TokenKind::Minus if lhs.is_pointer() && rhs.is_pointer() => Type::Long,
+            TokenKind::Plus | TokenKind::Minus if lhs.is_pointer() => lhs.clone(),
+            TokenKind::Plus if rhs.is_pointer() => rhs.clone(),
+            _ => Type::arithmetic(lhs, rhs),
+        }
+    }
+}
+
+/// What an ordinary identifier names. Variables, functions, typedef names and
+/// enumeration constants share one namespace.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolKind {
+    Object,
+    Typedef,
+    EnumConstant(i64),
 }
 
 #[derive(Debug, Clone)]
 pub struct Symbol {
     pub name: String,
     pub ty: Type,
+    pub kind: SymbolKind,
+    /// Whether the object lives as long as the program: a global, or a block-scope
+    /// `static` or `extern` variable. Only such objects have constant addresses.
+    pub is_static: bool,
     pub span: Span,
 }
 
+/// What a tag names. Struct, union and enum tags share one namespace, so
+/// `struct s` and `enum s` cannot both be defined.
+#[derive(Debug, Clone)]
+pub enum Tag {
+    Struct(Vec<(String, Type)>),
+    Union(Vec<(String, Type)>),
+    Enum,
+}
+
 #[derive(Debug)]
 pub struct SymbolTable {
     scopes: Vec<HashMap<String, Symbol>>,
+    /// Functions that have a body and variables that have been defined, as
+    /// opposed to only declared by a prototype or `extern`.
+    defined: HashSet<String>,
+    /// Globals with internal linkage, first declared `static`.
+    internal: HashSet<String>,
+    /// Struct, union and enum tags. Tags are a namespace of their own, so a struct
+    /// and a variable may share a name.
+    tags: HashMap<String, Tag>,
+    layout: TargetLayout,
 }
 
 impl SymbolTable {
-    pub fn new() -> Self {
-        SymbolTable { scopes: vec![HashMap::new()] }
+    pub fn new(layout: TargetLayout) -> Self {
+        SymbolTable { scopes: vec![HashMap::new()], defined: HashSet::new(), internal: HashSet::new(), tags: HashMap::new(), layout }
     }
 
     pub fn enter_scope(&mut self) {
@@ -34,27 +342,144 @@ impl SymbolTable {
         self.scopes.pop();
     }
 
+    /// Registers a global declaration. A function or variable may be declared any
+    /// number of times with the same type, but defined only once. A variable
+    /// without an initializer or `extern` is a tentative definition, which only
+    /// defines it, as zero, if no declaration gives it a value; an array declared
+    /// without a size takes it from another declaration. A name first declared
+    /// `static` has internal linkage, which later declarations may only repeat or
+    /// leave to `extern`.
     pub fn insert(&mut self, decl: &Decl) -> Result<(), SemanticError> {
-        let (name, ty, span) = match decl {
-            Decl::Function { name, .. } => (name.clone(), Type::Int, decl.span()),
-            Decl::Var { name, .. }      => (name.clone(), Type::Int, decl.span()),
+        let (name, ty, storage, span) = match decl {
+            Decl::Struct { name, members, is_union, span } => return self.insert_struct(name, members, *is_union, *span),
+            Decl::Enum { name, constants, span } => return self.insert_enum(name.as_deref(), constants, *span),
+            Decl::Typedef { name, ty, span } => return self.insert_typedef(name, ty, *span),
+            Decl::Function { name, ret_ty, params, variadic, storage, .. } => {
+                let ty = Type::Function {
+                    ret: Box::new(ret_ty.clone()),
+                    params: params.iter().map(|(_, ty)| ty.clone()).collect(),
+                    variadic: *variadic,
+                };
+                (name.clone(), ty, *storage, decl.span())
+            }
+            Decl::Var { name, ty, storage, .. } => (name.clone(), ty.clone(), *storage, decl.span()),
+        };
+        let defines = match decl {
+            Decl::Function { body, .. } => body.is_some(),
+            Decl::Var { init, .. } => init.is_some(),
+            _ => unreachable!(),
         };
+        if defines && !self.defined.insert(name.clone()) {
+            return Err(SemanticError::DuplicateSymbol(name, span));
+        }
 
+        let is_function = matches!(ty, Type::Function { .. });
+        let is_static = storage == Some(StorageClass::Static);
         let scope = self.scopes.last_mut().unwrap();
-        if scope.contains_key(&name) {
-            Err(SemanticError::DuplicateSymbol(name, span))
-        } else {
-            scope.insert(name.clone(), Symbol { name, ty, span });
-            Ok(())
+        match scope.get_mut(&name) {
+            None => {
+                if is_static {
+                    self.internal.insert(name.clone());
+                }
+                scope.insert(name.clone(), Symbol { name, ty, kind: SymbolKind::Object, is_static: true, span });
+                Ok(())
+            }
+            Some(existing) if existing.kind != SymbolKind::Object || matches!(existing.ty, Type::Function { .. }) != is_function => {
+                Err(SemanticError::DuplicateSymbol(name, span))
+            }
+            Some(existing) => {
+                let Some(ty) = composite(&existing.ty, &ty) else {
+                    return Err(SemanticError::ConflictingTypes(name, span));
+                };
+                // A function declared without a storage class behaves as if `extern`.
+                if self.internal.contains(&name) != is_static && (is_static || (storage.is_none() && !is_function)) {
+                    return Err(SemanticError::ConflictingLinkage(name, span));
+                }
+                existing.ty = ty;
+                Ok(())
+            }
         }
     }
 
-    pub fn insert_symbol(&mut self, name: String, ty: Type, span: Span) -> Result<(), SemanticError> {
+    /// Registers a struct or union definition. Members need distinct names and
+    /// complete types, so a struct cannot contain itself except through a pointer.
+    pub fn insert_struct(&mut self, name: &str, members: &[(String, Type)], is_union: bool, span: Span) -> Result<(), SemanticError> {
+        let keyword = if is_union { "union" } else { "struct" };
+        if self.tags.contains_key(name) {
+            return Err(SemanticError::DuplicateSymbol(format!("{} {}", keyword, name), span));
+        }
+        let mut seen = HashSet::new();
+        for (member, ty) in members {
+            if !seen.insert(member) {
+                return Err(SemanticError::DuplicateSymbol(member.clone(), span));
+            }
+            if !self.is_complete(ty) {
+                return Err(SemanticError::IncompleteType(format!("{:?}", ty), span));
+            }
+        }
+        let tag = if is_union { Tag::Union(members.to_vec()) } else { Tag::Struct(members.to_vec()) };
+        self.tags.insert(name.to_string(), tag);
+        Ok(())
+    }
+
+    /// Registers an enum definition: its tag, if it has one, and its constants,
+    /// which are ordinary identifiers of type `int`.
+    pub fn insert_enum(&mut self, name: Option<&str>, constants: &[(String, i64)], span: Span) -> Result<(), SemanticError> {
+        if let Some(name) = name {
+            if self.tags.contains_key(name) {
+                return Err(SemanticError::DuplicateSymbol(format!("enum {}", name), span));
+            }
+            self.tags.insert(name.to_string(), Tag::Enum);
+        }
+        for (constant, value) in constants {
+            self.declare(Symbol { name: constant.clone(), ty: Type::Int, kind: SymbolKind::EnumConstant(*value), is_static: false, span })?;
+        }
+        Ok(())
+    }
+
+    /// Registers a typedef name. Repeating a typedef is allowed as long as it
+    /// names the same type.
+    pub fn insert_typedef(&mut self, name: &str, ty: &Type, span: Span) -> Result<(), SemanticError> {
+        let scope = self.scopes.last_mut().unwrap();
+        match scope.get(name) {
+            Some(existing) if existing.kind == SymbolKind::Typedef && existing.ty == *ty => Ok(()),
+            Some(existing) if existing.kind == SymbolKind::Typedef => Err(SemanticError::ConflictingTypes(name.to_string(), span)),
+            _ => self.declare(Symbol { name: name.to_string(), ty: ty.clone(), kind: SymbolKind::Typedef, is_static: false, span }),
+        }
+    }
+
+    /// Members of the struct or union with tag `name`, if it has been defined.
+    pub fn struct_members(&self, name: &str) -> Option<&[(String, Type)]> {
+        match self.tags.get(name) {
+            Some(Tag::Struct(members) | Tag::Union(members)) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Whether objects of type `ty` can be created: `void`, functions and structs
+    /// whose tag has not been defined yet have no size.
+    pub fn is_complete(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Void | Type::Function { .. } => false,
+            Type::Struct(name) => matches!(self.tags.get(name), Some(Tag::Struct(_))),
+            Type::Union(name) => matches!(self.tags.get(name), Some(Tag::Union(_))),
+            Type::Array(_, 0) => false,
+            Type::Array(elem, _) | Type::Qualified(elem, _) => self.is_complete(elem),
+            _ => true,
+        }
+    }
+
+    pub fn insert_symbol(&mut self, name: String, ty: Type, is_static: bool, span: Span) -> Result<(), SemanticError> {
+        self.declare(Symbol { name, ty, kind: SymbolKind::Object, is_static, span })
+    }
+
+    /// Adds `symbol` to the innermost scope, which must not declare its name yet.
+    fn declare(&mut self, symbol: Symbol) -> Result<(), SemanticError> {
         let scope = self.scopes.last_mut().unwrap();
-        if scope.contains_key(&name) {
-            Err(SemanticError::DuplicateSymbol(name, span))
+        if scope.contains_key(&symbol.name) {
+            Err(SemanticError::DuplicateSymbol(symbol.name, symbol.span))
         } else {
-            scope.insert(name.clone(), Symbol { name, ty, span });
+            scope.insert(symbol.name.clone(), symbol);
             Ok(())
         }
     }
@@ -68,3 +493,27 @@ impl SymbolTable {
         None
     }
 }
+
+/// The type of a name declared as both `existing` and `ty`, if they agree: the
+/// same type, arrays of the same elements of which one leaves out the size, or
+/// functions returning the same type of which one leaves out the parameters.
+fn composite(existing: &Type, ty: &Type) -> Option<Type> {
+    match (existing, ty) {
+        _ if existing == ty => Some(ty.clone()),
+        (Type::Function { ret: a, .. }, Type::Function { ret: b, .. }) if a == b && existing.is_unprototyped() => Some(ty.clone()),
+        (Type::Function { ret: a, .. }, Type::Function { ret: b, .. }) if a == b && ty.is_unprototyped() => Some(existing.clone()),
+        (Type::Array(a, 0), Type::Array(b, _)) if a == b => Some(ty.clone()),
+        (Type::Array(a, _), Type::Array(b, 0)) if a == b => Some(existing.clone()),
+        _ => None,
+    }
+}
+
+impl Layout for SymbolTable {
+    fn members(&self, tag: &str) -> Option<&[(String, Type)]> {
+        self.struct_members(tag)
+    }
+
+    fn target(&self) -> &TargetLayout {
+        &self.layout
+    }
+}
@@ -1,4 +1,7 @@
-use cmpler_core::driver::compile_to_llvm_ir;
+use cmpler_core::codegen::target::target_machine;
+use cmpler_core::driver::{compile_for_target, compile_to_llvm_ir, compile_to_llvm_ir_for_target};
+use cmpler_core::error::CompilerError;
+use inkwell::targets::TargetTriple;
 use inkwell::OptimizationLevel;
 
 #[test]
@@ -160,3 +163,473 @@ fn codegen_nested_if_else() {
     assert_eq!(ir.matches("store i32 6").count(), 1, "Expected store 6, got: {}", ir);
     assert_eq!(ir.matches("store i32 7").count(), 1, "Expected store 7, got: {}", ir);
 }
+
+#[test]
+fn codegen_params_and_recursive_call() {
+    let src = r#"
+        int main() { return fact(5); }
+        int fact(int n) {
+            if (n < 2) { return 1; }
+            return n * fact(n - 1);
+        }
+    "#;
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("define i32 @fact(i32 %n)"), "Expected parameter in signature, got: {}", ir);
+    assert!(ir.contains("call i32 @fact(i32 5)"), "Expected forward call from main, got: {}", ir);
+    assert!(ir.contains("call i32 @fact(i32 %subtmp)"), "Expected recursive call, got: {}", ir);
+    assert!(ir.contains("store i32 %n"), "Expected parameter spilled to alloca, got: {}", ir);
+}
+
+#[test]
+fn codegen_short_circuit_and() {
+    let src = "int f(int d) { if (d != 0 && 10 / d > 1) { return 1; } return 0; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("br i1 %netmp, label %and.rhs, label %and.end"), "Expected short-circuit branch, got: {}", ir);
+    assert!(ir.contains("phi i1 [ false, %entry ], [ %gttmp, %and.rhs ]"), "Expected phi merging both arms, got: {}", ir);
+}
+
+#[test]
+fn codegen_logical_ops_normalized_to_int() {
+    let src = "int f(int a, int b) { int x = a || !b; return x; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("or.rhs"), "Expected short-circuit block for ||, got: {}", ir);
+    assert!(ir.contains("zext i1 %ortmp to i32"), "Expected result widened to int, got: {}", ir);
+}
+
+#[test]
+fn codegen_unary_operators() {
+    let src = "int f(int x) { x = -x; return ~x + +x; } int main() { return -1; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("sub i32 0, %x"), "Expected negation, got: {}", ir);
+    assert!(ir.contains("xor i32 %x"), "Expected bitwise not, got: {}", ir);
+    assert!(ir.contains("ret i32 -1"), "Expected folded negative constant, got: {}", ir);
+}
+
+#[test]
+fn codegen_global_variables() {
+    let src = r#"
+        int counter = 2 * 3 - 10;
+        int bump() { counter = counter + 1; return counter; }
+        int main() { int counter = 5; return counter; }
+    "#;
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@counter = global i32 -4"), "Expected folded global initializer, got: {}", ir);
+    assert!(ir.contains("load i32, i32* @counter") || ir.contains("load i32, ptr @counter"), "Expected load from global, got: {}", ir);
+    assert!(ir.contains("@counter, align"), "Expected store to global, got: {}", ir);
+    assert_eq!(ir.matches("alloca i32").count(), 1, "Expected local to shadow the global, got: {}", ir);
+}
+
+#[test]
+fn codegen_void_function() {
+    let src = "int n = 0; void inc() { n = n + 1; } void early() { return; } int main() { inc(); return n; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("define void @inc()"), "Expected void function, got: {}", ir);
+    assert_eq!(ir.matches("ret void").count(), 2, "Expected implicit and explicit ret void, got: {}", ir);
+    assert!(ir.contains("call void @inc()"), "Expected void call, got: {}", ir);
+}
+
+#[test]
+fn codegen_break_and_continue_targets() {
+    let src = r#"
+        int main() {
+            int i = 0;
+            int sum = 0;
+            for (i = 0; i < 10; i = i + 1) {
+                if (i == 3) { continue; }
+                if (i == 8) { break; }
+                sum = sum + i;
+            }
+            while (1) { break; }
+            return sum;
+        }
+    "#;
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("br label %inc"), "Expected continue to jump to the increment, got: {}", ir);
+    assert!(ir.contains("br label %cont\n"), "Expected break to jump past the loop, got: {}", ir);
+    assert!(ir.contains("br i1 %lttmp, label %body, label %cont"), "Expected loop condition check, got: {}", ir);
+}
+
+#[test]
+fn codegen_do_while_runs_body_first() {
+    let src = "int main() { int i = 0; do { i = i + 1; } while (i < 3); return i; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    let entry_branch = ir.find("br label %body").expect("Expected entry to branch into the body");
+    let cond_check = ir.find("icmp slt").expect("Expected loop condition");
+    assert!(entry_branch < cond_check, "Expected body before condition, got: {}", ir);
+    assert!(ir.contains("br i1 %lttmp, label %body, label %cont"), "Expected back-edge to body, got: {}", ir);
+}
+
+#[test]
+fn codegen_switch_instruction_with_fallthrough() {
+    let src = r#"
+        int f(int x) {
+            int r = 0;
+            switch (x) {
+                case 1: r = 10;
+                case 2: r = r + 20; break;
+                default: r = 7;
+            }
+            return r;
+        }
+    "#;
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("switch i32 %x"), "Expected switch instruction, got: {}", ir);
+    assert!(ir.contains("i32 1, label %case") && ir.contains("i32 2, label %case"), "Expected case table, got: {}", ir);
+    assert!(ir.contains("label %default ["), "Expected default destination, got: {}", ir);
+    assert!(ir.contains("br label %case"), "Expected fallthrough into the next case, got: {}", ir);
+    assert!(ir.contains("br label %sw.end"), "Expected break to leave the switch, got: {}", ir);
+}
+
+#[test]
+fn codegen_increment_returns_old_or_new_value() {
+    let src = "int f(int i) { int a = i++; int b = ++i; return a + b; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("store i32 %oldval, i32* %a") || ir.contains("store i32 %oldval, ptr %a"), "Expected postfix to yield the old value, got: {}", ir);
+    assert!(ir.contains("store i32 %inctmp3, i32* %b") || ir.contains("store i32 %inctmp3, ptr %b"), "Expected prefix to yield the new value, got: {}", ir);
+}
+
+#[test]
+fn codegen_compound_assignment_single_address() {
+    let src = "int g = 1; int main() { g += 4; g %= 3; return g; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("add i32 %oldval, 4"), "Expected add on loaded value, got: {}", ir);
+    assert!(ir.contains("srem i32 %oldval1, 3"), "Expected srem for %=, got: {}", ir);
+}
+
+#[test]
+fn codegen_bitwise_and_shift_instructions() {
+    let src = "int f(int a, int b) { return (a % b) + (a & b) + (a | b) + (a ^ b) + (a << b) + (a >> b); }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    for instr in ["srem i32", "and i32", "or i32", "xor i32", "shl i32", "ashr i32"] {
+        assert!(ir.contains(instr), "Expected {}, got: {}", instr, ir);
+    }
+}
+
+#[test]
+fn codegen_bitwise_constant_global() {
+    let src = "int mask = (1 << 4) | 3 ^ 1;";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@mask = global i32 18"), "Expected folded global, got: {}", ir);
+}
+
+#[test]
+fn codegen_conditional_branches_and_phi() {
+    let src = "int f(int a, int b) { return b != 0 ? a / b : 0; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("cond.true:") && ir.contains("cond.false:"), "Expected arm blocks, got: {}", ir);
+    assert!(ir.contains("%condtmp = phi i32"), "Expected phi for result, got: {}", ir);
+}
+
+#[test]
+fn codegen_conditional_constant_global() {
+    let src = "int g = 2 > 1 ? 10 : 20;";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@g = global i32 10"), "Expected folded global, got: {}", ir);
+}
+
+#[test]
+fn codegen_address_constant_globals() {
+    let src = "extern int x; int *p = &x; int x = 1; int arr[4]; int *q = arr; int *r = &arr[1]; char *s = \"abc\" + 1;";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@p = global ptr @x"), "Expected address of later global, got: {}", ir);
+    assert!(ir.contains("@q = global ptr @arr"), "Expected decayed array, got: {}", ir);
+    assert!(ir.contains("@r = global ptr getelementptr inbounds (i32, ptr @arr, i64 1)"), "Expected element address, got: {}", ir);
+    assert!(ir.contains("@s = global ptr getelementptr inbounds (i8, ptr @.str, i64 1)"), "Expected string offset, got: {}", ir);
+}
+
+#[test]
+fn codegen_uninitialized_declarations() {
+    let src = "int a, b = 4; int main() { int x, y = 2; x = y + b; return x + a; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@a = global i32 0"), "Expected zero-initialized global, got: {}", ir);
+    assert!(ir.contains("@b = global i32 4"), "Expected initialized global, got: {}", ir);
+    assert!(ir.contains("%x = alloca i32"), "Expected alloca for uninitialized local, got: {}", ir);
+}
+
+#[test]
+fn codegen_for_declaration_and_continue() {
+    let src = "int main() { int s = 0; for (int i = 0; i < 10; i++) { if (i % 2) continue; s += i; } return s; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("%i = alloca i32"), "Expected alloca for loop variable, got: {}", ir);
+    assert!(ir.contains("br label %inc"), "Expected continue to branch to increment, got: {}", ir);
+}
+
+#[test]
+fn codegen_char_promotes_to_int() {
+    let src = "char g = 'A'; char next(char c) { return c + 1; } int main() { char c = next(g); return c * 2; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@g = global i8 65"), "Expected i8 global, got: {}", ir);
+    assert!(ir.contains("define i8 @next(i8 %c)"), "Expected i8 signature, got: {}", ir);
+    assert!(ir.contains("sext i8"), "Expected sign extension to int, got: {}", ir);
+    assert!(ir.contains("trunc i32"), "Expected truncation back to char, got: {}", ir);
+}
+
+#[test]
+fn codegen_string_literals_deduplicated() {
+    let src = r#"int main() { "hi\n"; "other"; "hi\n"; return 0; }"#;
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(
+        ir.contains(r#"@.str = private unnamed_addr constant [4 x i8] c"hi\0A\00""#),
+        "Expected private string constant, got: {}",
+        ir
+    );
+    assert!(ir.contains(r#"c"other\00""#), "Expected second literal, got: {}", ir);
+    assert_eq!(ir.matches("private unnamed_addr constant").count(), 2, "Expected identical literals to share a global, got: {}", ir);
+}
+
+#[test]
+fn codegen_external_declarations() {
+    let src = r#"int putchar(int c); int printf(char *fmt, ...); int main() { char c = 'x'; putchar(c); printf("%c%d\n", c, 1); return 0; }"#;
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("declare i32 @putchar(i32)"), "Expected putchar declaration, got: {}", ir);
+    assert!(ir.contains("declare i32 @printf(ptr, ...)"), "Expected variadic printf declaration, got: {}", ir);
+    assert!(ir.contains("call i32 (ptr, ...) @printf(ptr @.str, i32 %convtmp"), "Expected promoted variadic argument, got: {}", ir);
+}
+
+#[test]
+fn codegen_address_of_and_dereference() {
+    let src = "int main() { int x = 1; int *p = &x; int **pp = &p; **pp = 5; return *p; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("%pp = alloca ptr"), "Expected pointer-to-pointer alloca, got: {}", ir);
+    assert!(ir.contains("store ptr %x, ptr %p"), "Expected address of x stored in p, got: {}", ir);
+    assert!(ir.contains("store i32 5, ptr"), "Expected store through pointer, got: {}", ir);
+}
+
+#[test]
+fn codegen_pointer_arithmetic() {
+    let src = "int f(int *p, int *q) { p = p + 2; p--; if (p < q) return 0; return p - q; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("getelementptr inbounds i32, ptr"), "Expected element-scaled GEP, got: {}", ir);
+    assert!(ir.contains("i64 -1"), "Expected decrement by one element, got: {}", ir);
+    assert!(ir.contains("icmp ult ptr"), "Expected unsigned pointer comparison, got: {}", ir);
+    assert!(ir.contains("sdiv exact i64"), "Expected pointer difference in elements, got: {}", ir);
+}
+
+#[test]
+fn codegen_array_layout_and_indexing() {
+    let src = "int g[2][3] = {{1, 2, 3}, {4}}; char s[] = \"hi\"; \
+               int main() { int a[4] = {7}; a[2] = g[1][0]; int *p = a; return p[2] + s[0]; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(
+        ir.contains("@g = global [2 x [3 x i32]] [[3 x i32] [i32 1, i32 2, i32 3], [3 x i32] [i32 4, i32 0, i32 0]]"),
+        "Expected constant nested array initializer, got: {}",
+        ir
+    );
+    assert!(ir.contains(r#"@s = global [3 x i8] c"hi\00""#), "Expected sized char array, got: {}", ir);
+    assert!(ir.contains("%a = alloca [4 x i32]"), "Expected local array alloca, got: {}", ir);
+    assert!(ir.contains("store [4 x i32] zeroinitializer, ptr %a"), "Expected zero fill before initializers, got: {}", ir);
+    assert!(ir.contains("getelementptr inbounds [4 x i32], ptr %a, i64 0, i64"), "Expected array element address, got: {}", ir);
+    assert!(ir.contains("store ptr %a, ptr %p"), "Expected array to decay to a pointer, got: {}", ir);
+}
+
+#[test]
+fn codegen_brace_elided_array_initializers() {
+    let src = "const int g[2][2] = {1, 2, 3}; int main() { int m[2][2] = {1, 2, 3, 4}; return m[1][0] + g[1][0]; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(
+        ir.contains("@g = constant [2 x [2 x i32]] [[2 x i32] [i32 1, i32 2], [2 x i32] [i32 3, i32 0]]"),
+        "Expected rows filled in order, got: {}",
+        ir
+    );
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::Default).unwrap();
+    assert!(ir.contains("ret i32 6"), "Expected m[1][0] to be 3, got: {}", ir);
+}
+
+#[test]
+fn codegen_struct_types_and_member_access() {
+    let src = "struct point { int x; char c; struct point *next; }; struct point g = {1, 'a'}; \
+               int main() { struct point p = g; p.next = &g; p.next->x = 4; return p.x; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("target datalayout = "), "Expected the host data layout, got: {}", ir);
+    assert!(ir.contains("%struct.point = type { i32, i8, ptr }"), "Expected named struct type, got: {}", ir);
+    assert!(ir.contains("@g = global %struct.point { i32 1, i8 97, ptr null }"), "Expected constant struct, got: {}", ir);
+    assert!(ir.contains("getelementptr inbounds %struct.point, ptr %p, i32 0, i32 2"), "Expected struct GEP, got: {}", ir);
+    assert!(ir.contains("load %struct.point, ptr @g"), "Expected struct copy, got: {}", ir);
+}
+
+#[test]
+fn codegen_integer_widths_and_signedness() {
+    let src = "long g = 1L << 40; \
+               unsigned f(unsigned a, int b, unsigned char c, short s) { long w = s; if (a < b) return a / c; return (a >> 1) % w + b / 2; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@g = global i64 1099511627776"), "Expected 64-bit long global, got: {}", ir);
+    assert!(ir.contains("define i32 @f(i32 %a, i32 %b, i8 %c, i16 %s)"), "Expected narrow parameter types, got: {}", ir);
+    assert!(ir.contains("sext i16"), "Expected signed short to sign-extend, got: {}", ir);
+    assert!(ir.contains("zext i8"), "Expected unsigned char to zero-extend, got: {}", ir);
+    assert!(ir.contains("icmp ult i32"), "Expected unsigned comparison, got: {}", ir);
+    assert!(ir.contains("udiv i32"), "Expected unsigned division, got: {}", ir);
+    assert!(ir.contains("lshr i32"), "Expected logical right shift, got: {}", ir);
+    assert!(ir.contains("srem i64"), "Expected signed remainder in long, got: {}", ir);
+    assert!(ir.contains("sdiv i32"), "Expected signed division of int operands, got: {}", ir);
+}
+
+#[test]
+fn codegen_floating_point() {
+    let src = "int printf(char *fmt, ...); double g = 0.5; \
+               int main() { float f = 1.5f; int i = 2; double d = f * i + g; printf(\"%f\", f); return d < 3.0; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@g = global double 5.000000e-01"), "Expected double global, got: {}", ir);
+    assert!(ir.contains("store float 1.500000e+00, ptr %f"), "Expected float constant, got: {}", ir);
+    assert!(ir.contains("sitofp i32"), "Expected int to float conversion, got: {}", ir);
+    assert!(ir.contains("fmul float"), "Expected float multiplication, got: {}", ir);
+    assert!(ir.contains("fpext float"), "Expected float widened to double, got: {}", ir);
+    assert!(ir.contains("fadd double"), "Expected double addition, got: {}", ir);
+    assert!(ir.contains("fcmp olt double"), "Expected ordered comparison, got: {}", ir);
+    assert!(ir.contains("call i32 (ptr, ...) @printf(ptr @.str, double %convtmp"), "Expected float argument promoted to double, got: {}", ir);
+}
+
+#[test]
+fn codegen_casts_and_sizeof() {
+    let src = "struct pair { char c; double d; }; \
+               long f(double x, int *p) { int a[5]; return (int)x + (long)p + sizeof a + sizeof(struct pair) + (char)x; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("fptosi double"), "Expected float to int cast, got: {}", ir);
+    assert!(ir.contains("ptrtoint ptr"), "Expected pointer to integer cast, got: {}", ir);
+    assert!(ir.contains("add i64 %addtmp, 20"), "Expected sizeof of the array from the data layout, got: {}", ir);
+    assert!(ir.contains("add i64 %addtmp6, 16"), "Expected padded struct size, got: {}", ir);
+}
+
+#[test]
+fn codegen_union_layout_and_enum_constants() {
+    let src = "enum { ONE = 1, TWO }; union u { char c; double d; int i; }; union u g = { 'a' }; \
+               int main() { union u v; v.i = TWO; return v.c + sizeof(union u); }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("%union.u = type { double }"), "Expected union laid out as its widest member, got: {}", ir);
+    assert!(ir.contains("@g = global <{ i8, [7 x i8] }> <{ i8 97, [7 x i8] zeroinitializer }>, align 8"), "Expected union initialized through its first member, got: {}", ir);
+    assert!(ir.contains("store i32 2, ptr %v"), "Expected member stored at the union's address, got: {}", ir);
+    assert!(ir.contains("load i8, ptr %v"), "Expected overlapping member load, got: {}", ir);
+}
+
+#[test]
+fn codegen_linkage_static_locals_and_volatile() {
+    let src = "static int hidden = 1; extern int outside; const int limit = 3; volatile int flag; \
+               static int helper(void) { return outside; } \
+               int tick(void) { static int calls; flag = 1; while (flag) {} return ++calls + helper() + limit + hidden; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::Default).unwrap();
+    assert!(ir.contains("@hidden = internal global i32 1"), "Expected internal global, got: {}", ir);
+    assert!(ir.contains("@outside = external global i32"), "Expected external declaration, got: {}", ir);
+    assert!(ir.contains("@limit = constant i32 3"), "Expected const global emitted as a constant, got: {}", ir);
+    assert!(ir.contains("@tick.calls = internal global i32 0"), "Expected static local as an internal global, got: {}", ir);
+    assert!(ir.contains("define internal i32 @helper()"), "Expected internal function, got: {}", ir);
+    assert!(ir.contains("store volatile i32 1, ptr @flag"), "Expected volatile store, got: {}", ir);
+    assert!(ir.contains("load volatile i32, ptr @flag"), "Expected volatile load kept by the optimizer, got: {}", ir);
+}
+
+#[test]
+fn codegen_unsigned_constant_folding() {
+    let src = "unsigned g1 = -1u / 2; int g2 = (0u - 1) > 0; int a[(0u - 1) >> 28]; long g3 = -1u; \
+               unsigned long g4 = -1ul % 10; int g5 = -8 >> 1;";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@g1 = global i32 2147483647"), "Expected unsigned division, got: {}", ir);
+    assert!(ir.contains("@g2 = global i32 1"), "Expected unsigned comparison, got: {}", ir);
+    assert!(ir.contains("@a = global [15 x i32]"), "Expected logical shift in array size, got: {}", ir);
+    assert!(ir.contains("@g3 = global i64 4294967295"), "Expected unsigned int widened without sign, got: {}", ir);
+    assert!(ir.contains("@g4 = global i64 5"), "Expected 64-bit unsigned remainder, got: {}", ir);
+    assert!(ir.contains("@g5 = global i32 -4"), "Expected arithmetic shift of a signed value, got: {}", ir);
+}
+
+#[test]
+fn codegen_struct_in_prototype_before_definition() {
+    let src = "struct s; int f(struct s x); struct s { int a; }; int f(struct s x) { return x.a; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("%struct.s = type { i32 }"), "Expected struct body set after the prototype, got: {}", ir);
+    assert!(ir.contains("define i32 @f(%struct.s %x)"), "Expected struct parameter, got: {}", ir);
+}
+
+#[test]
+fn codegen_constant_casts() {
+    let src = "int *gp = (int *)16; int *np = (int *)0; int g3 = (char)300; unsigned char g4 = (unsigned char)-1; \
+               int g5 = (int)-2.7; unsigned g6 = (unsigned short)70000 + (unsigned)3e9;";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@gp = global ptr inttoptr (i64 16 to ptr)"), "Expected integer address kept, got: {}", ir);
+    assert!(ir.contains("@np = global ptr null"), "Expected null pointer, got: {}", ir);
+    assert!(ir.contains("@g3 = global i32 44"), "Expected cast truncated to char, got: {}", ir);
+    assert!(ir.contains("@g4 = global i8 -1"), "Expected unsigned char 255, got: {}", ir);
+    assert!(ir.contains("@g5 = global i32 -2"), "Expected truncation toward zero, got: {}", ir);
+    assert!(ir.contains("@g6 = global i32 -1294962832"), "Expected unsigned casts folded, got: {}", ir);
+}
+
+#[test]
+fn codegen_sizeof_string_literal() {
+    let src = "long f() { return sizeof \"hello\"; } char g() { return \"abc\"[1]; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("ret i64 6"), "Expected literal sized with its NUL, got: {}", ir);
+    assert!(ir.contains("getelementptr inbounds ([4 x i8], ptr @.str, i64 0, i64 1)"), "Expected literal indexed as an array, got: {}", ir);
+}
+
+#[test]
+fn codegen_sizeof_global_initializer() {
+    let src = "struct s { char c; long l; }; unsigned long n = sizeof(int); int m = sizeof(struct s) * 2; int a[5]; long k = sizeof a;";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@n = global i64 4"), "Expected folded sizeof(int), got: {}", ir);
+    assert!(ir.contains("@m = global i32 32"), "Expected folded struct size, got: {}", ir);
+    assert!(ir.contains("@k = global i64 20"), "Expected folded array size, got: {}", ir);
+}
+
+#[test]
+fn codegen_sizeof_follows_target_layout() {
+    let src = "struct s { char c; double d; }; int p[sizeof(void *)]; int l = sizeof(long); int n = sizeof(struct s); long f(long x) { return x; }";
+    for (triple, pointer, long, size) in [("x86_64-unknown-linux-gnu", 8, 8, 16), ("i686-unknown-linux-gnu", 4, 4, 12)] {
+        let tm = target_machine(&TargetTriple::create(triple), OptimizationLevel::None).unwrap();
+        let ir = compile_to_llvm_ir_for_target(src, OptimizationLevel::None, &tm).unwrap();
+        assert!(ir.contains(&format!("@p = global [{} x i32] zeroinitializer", pointer)), "Expected sizeof(void *) for {}, got: {}", triple, ir);
+        assert!(ir.contains(&format!("@l = global i32 {}", long)), "Expected sizeof(long) for {}, got: {}", triple, ir);
+        assert!(ir.contains(&format!("@n = global i32 {}", size)), "Expected struct size for {}, got: {}", triple, ir);
+        assert!(ir.contains(&format!("define i{} @f(i{} %x)", long * 8, long * 8)), "Expected target-sized long for {}, got: {}", triple, ir);
+    }
+    let src = "int f(int x) { switch (x) { case sizeof(long): return 1; case 4: return 2; } return 0; }";
+    let tm = target_machine(&TargetTriple::create("i686-unknown-linux-gnu"), OptimizationLevel::None).unwrap();
+    assert!(matches!(compile_for_target(src, &tm), Err(CompilerError::Semantic(_))), "Expected duplicate case on a 32-bit target");
+}
+
+#[test]
+fn codegen_tentative_definitions() {
+    let src = "int x; int x; int y; int y = 3; extern int a[]; int f() { return a[1] + b[0]; } int a[2] = {4, 5}; extern int b[];";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@x = global i32 0"), "Expected one zeroed x, got: {}", ir);
+    assert!(ir.contains("@y = global i32 3"), "Expected y from its initializer, got: {}", ir);
+    assert!(ir.contains("@a = global [2 x i32] [i32 4, i32 5]"), "Expected sized a, got: {}", ir);
+    assert!(ir.contains("@b = external global [0 x i32]"), "Expected unsized external b, got: {}", ir);
+    assert!(!ir.contains("@x.1") && !ir.contains("@y.1"), "Expected no duplicate globals, got: {}", ir);
+}
+
+#[test]
+fn codegen_block_scope_tags() {
+    let src = "struct s { int a; };
+               long f() { struct s { char c[10]; long l; } v; return sizeof v; }
+               long g() { struct s v; return sizeof v; }
+               int h() { enum { N = 4 }; int a[N]; return sizeof a; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("ret i64 24"), "Expected the block's struct s, got: {}", ir);
+    assert!(ir.contains("ret i64 4"), "Expected the file-scope struct s, got: {}", ir);
+    assert!(ir.contains("alloca [4 x i32]"), "Expected an array sized by a block enum, got: {}", ir);
+}
+
+#[test]
+fn codegen_function_name_as_value() {
+    let src = "int f(void) { return 1; } int main(void) { f; void *p = f; return p == (void *)&f; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("store ptr @f, ptr %p"), "Expected the function's address, got: {}", ir);
+    // Function pointer declarators are not supported; they are a parse error
+    // rather than a crash.
+    let src = "int f(void) { return 1; } int main(void) { int (*p)(void) = f; return 0; }";
+    assert!(matches!(compile_to_llvm_ir(src, OptimizationLevel::None), Err(CompilerError::Parse(_))));
+}
+
+#[test]
+fn codegen_conditional_float_global_initializer() {
+    let src = "double d = 1 ? 2.0 : 3.0; double e = 0 ? 2 : 3.5; float f = 1.5 ? 4 : 0.5;";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("@d = global double 2.000000e+00"), "Expected the chosen arm, got: {}", ir);
+    assert!(ir.contains("@e = global double 3.500000e+00"), "Expected the chosen arm, got: {}", ir);
+    assert!(ir.contains("@f = global float 4.000000e+00"), "Expected the chosen arm, got: {}", ir);
+}
+
+#[test]
+fn codegen_unprototyped_declarations() {
+    let src = "int puts(); int add(); int main(void) { puts(\"hi\"); return add(1, 2); } int add(int a, int b) { return a + b; }";
+    let ir = compile_to_llvm_ir(src, OptimizationLevel::None).unwrap();
+    assert!(ir.contains("declare i32 @puts(...)"), "Expected an unprototyped declaration, got: {}", ir);
+    assert!(ir.contains("define i32 @add(i32 %a, i32 %b)"), "Expected add's prototype to win, got: {}", ir);
+    assert!(ir.contains("call i32 @add(i32 1, i32 2)"), "Expected a prototyped call, got: {}", ir);
+}
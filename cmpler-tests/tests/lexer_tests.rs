@@ -2,7 +2,7 @@ use cmpler_core::lexer::{lex, TokenKind};
 
 #[test]
 fn test_keywords() {
-    let input = "int if else return while";
+    let input = "int if else return while do break continue";
     let kinds: Vec<_> = lex(input).into_iter().map(|t| t.kind).collect();
     assert_eq!(
         kinds,
@@ -12,6 +12,9 @@ fn test_keywords() {
             TokenKind::Else,
             TokenKind::Return,
             TokenKind::While,
+            TokenKind::Do,
+            TokenKind::Break,
+            TokenKind::Continue,
         ]
     );
 }
@@ -60,6 +63,23 @@ fn test_operators_and_punctuation() {
     );
 }
 
+#[test]
+fn test_unary_operators() {
+    let input = "-x ~y !z";
+    let kinds: Vec<_> = lex(input).into_iter().map(|t| t.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Minus,
+            TokenKind::Identifier,
+            TokenKind::Tilde,
+            TokenKind::Identifier,
+            TokenKind::LogicalNot,
+            TokenKind::Identifier,
+        ]
+    );
+}
+
 #[test]
 fn test_mixed_spacing() {
     let input = "int   x= 42 ;";
@@ -138,3 +158,177 @@ fn test_full_smallc_lexing() {
         ]
     );
 }
+
+#[test]
+fn test_switch_keywords() {
+    let input = "switch case default :";
+    let kinds: Vec<_> = lex(input).into_iter().map(|t| t.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Switch,
+            TokenKind::Case,
+            TokenKind::Default,
+            TokenKind::Colon,
+        ]
+    );
+}
+
+#[test]
+fn test_compound_assignment_and_increment() {
+    let input = "+= -= *= /= %= ++ -- a+++b";
+    let kinds: Vec<_> = lex(input).into_iter().map(|t| t.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::PlusAssign,
+            TokenKind::MinusAssign,
+            TokenKind::StarAssign,
+            TokenKind::SlashAssign,
+            TokenKind::PercentAssign,
+            TokenKind::PlusPlus,
+            TokenKind::MinusMinus,
+            TokenKind::Identifier,
+            TokenKind::PlusPlus,
+            TokenKind::Plus,
+            TokenKind::Identifier,
+        ]
+    );
+}
+
+#[test]
+fn test_bitwise_and_shift_operators() {
+    let input = "% & | ^ << >> && || &= |= ^= <<= >>=";
+    let kinds: Vec<_> = lex(input).into_iter().map(|t| t.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Percent,
+            TokenKind::Ampersand,
+            TokenKind::Pipe,
+            TokenKind::Caret,
+            TokenKind::ShiftLeft,
+            TokenKind::ShiftRight,
+            TokenKind::LogicalAnd,
+            TokenKind::LogicalOr,
+            TokenKind::AmpersandAssign,
+            TokenKind::PipeAssign,
+            TokenKind::CaretAssign,
+            TokenKind::ShiftLeftAssign,
+            TokenKind::ShiftRightAssign,
+        ]
+    );
+}
+
+#[test]
+fn test_char_keyword_and_literals() {
+    let input = r"char c = 'a'; '\n' '\'' '\x7f' '\101'";
+    let kinds: Vec<_> = lex(input).into_iter().map(|t| t.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Char,
+            TokenKind::Identifier,
+            TokenKind::Assign,
+            TokenKind::CharLiteral,
+            TokenKind::Semicolon,
+            TokenKind::CharLiteral,
+            TokenKind::CharLiteral,
+            TokenKind::CharLiteral,
+            TokenKind::CharLiteral,
+        ]
+    );
+}
+
+#[test]
+fn test_string_literals() {
+    let input = r#""hello\n" "" "say \"hi\"""#;
+    let tokens = lex(input);
+    let kinds: Vec<_> = tokens.iter().map(|t| t.kind.clone()).collect();
+    assert_eq!(kinds, vec![TokenKind::StringLiteral; 3]);
+    assert_eq!(tokens[2].text, r#""say \"hi\"""#);
+}
+
+#[test]
+fn test_brackets() {
+    let kinds: Vec<_> = lex("a[1]").iter().map(|t| t.kind.clone()).collect();
+    assert_eq!(kinds, vec![TokenKind::Identifier, TokenKind::LBracket, TokenKind::IntegerLiteral, TokenKind::RBracket]);
+}
+
+#[test]
+fn test_member_access_tokens() {
+    let kinds: Vec<_> = lex("struct s.x->y...").iter().map(|t| t.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Struct,
+            TokenKind::Identifier,
+            TokenKind::Dot,
+            TokenKind::Identifier,
+            TokenKind::Arrow,
+            TokenKind::Identifier,
+            TokenKind::Ellipsis,
+        ]
+    );
+}
+
+#[test]
+fn test_integer_type_keywords_and_suffixes() {
+    let tokens = lex("unsigned long long signed short 0x1Fu 017 10ul 5LL");
+    let kinds: Vec<_> = tokens.iter().map(|t| t.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Unsigned,
+            TokenKind::Long,
+            TokenKind::Long,
+            TokenKind::Signed,
+            TokenKind::Short,
+            TokenKind::IntegerLiteral,
+            TokenKind::IntegerLiteral,
+            TokenKind::IntegerLiteral,
+            TokenKind::IntegerLiteral,
+        ]
+    );
+    assert_eq!(tokens[5].text, "0x1Fu");
+    assert_eq!(tokens[7].text, "10ul");
+}
+
+#[test]
+fn test_float_literals() {
+    let tokens = lex("float double 1.5 .5e-3 2.f 1e10 3 s.x");
+    let kinds: Vec<_> = tokens.iter().map(|t| t.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Float,
+            TokenKind::Double,
+            TokenKind::FloatLiteral,
+            TokenKind::FloatLiteral,
+            TokenKind::FloatLiteral,
+            TokenKind::FloatLiteral,
+            TokenKind::IntegerLiteral,
+            TokenKind::Identifier,
+            TokenKind::Dot,
+            TokenKind::Identifier,
+        ]
+    );
+    assert_eq!(tokens[3].text, ".5e-3");
+}
+
+#[test]
+fn test_typedef_enum_union_keywords() {
+    let tokens = lex("typedef enum union unions");
+    let kinds: Vec<_> = tokens.iter().map(|t| t.kind.clone()).collect();
+    assert_eq!(kinds, vec![TokenKind::Typedef, TokenKind::Enum, TokenKind::Union, TokenKind::Identifier]);
+}
+
+#[test]
+fn test_storage_class_and_qualifier_keywords() {
+    let tokens = lex("static extern const volatile constant");
+    let kinds: Vec<_> = tokens.iter().map(|t| t.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        vec![TokenKind::Static, TokenKind::Extern, TokenKind::Const, TokenKind::Volatile, TokenKind::Identifier]
+    );
+}
@@ -1,22 +1,31 @@
 use cmpler_core::parser::Parser;
 use cmpler_core::parser::ParseError;
 use cmpler_core::lexer::lex;
-use cmpler_core::ast::{Decl, Stmt, Expr};
+use cmpler_core::ast::{Decl, Stmt, Expr, StorageClass};
 use cmpler_core::lexer::TokenKind;
+use cmpler_core::semantic::symbol_table::{Qualifiers, Type};
+use cmpler_core::semantic::layout::TargetLayout;
+use cmpler_core::codegen::target::host_target_machine;
+use inkwell::OptimizationLevel;
+
+/// Layout of the host, which `sizeof` folds for.
+fn layout() -> TargetLayout {
+    TargetLayout::new(&host_target_machine(OptimizationLevel::None).unwrap().get_target_data())
+}
 
 #[test]
 fn parse_simple_function() {
     let src = "int main() { return 42; }";
-    let mut parser = Parser::new(lex(src));
+    let mut parser = Parser::new(lex(src), layout());
     let program = parser.parse_program().expect("Failed to parse function");
     assert_eq!(program.decls.len(), 1);
     match &program.decls[0] {
-        Decl::Function { name, params, body, .. } => {
+        Decl::Function { name, params, body: Some(body), .. } => {
             assert_eq!(name, "main");
             assert!(params.is_empty());
             assert_eq!(body.len(), 1);
             match &body[0] {
-                Stmt::Return(expr) => match **expr {
+                Stmt::Return(Some(expr), _) => match **expr {
                     Expr::IntLiteral { value, .. } => assert_eq!(value, 42),
                     _ => panic!("Expected integer literal in return"),
                 },
@@ -30,11 +39,11 @@ fn parse_simple_function() {
 #[test]
 fn parse_variable_declaration() {
     let src = "int x = 5;";
-    let mut parser = Parser::new(lex(src));
+    let mut parser = Parser::new(lex(src), layout());
     let program = parser.parse_program().expect("Failed to parse var decl");
     assert_eq!(program.decls.len(), 1);
     match &program.decls[0] {
-        Decl::Var { name, init, .. } => {
+        Decl::Var { name, init: Some(init), .. } => {
             assert_eq!(name, "x");
             match **init {
                 Expr::IntLiteral { value, .. } => assert_eq!(value, 5),
@@ -48,10 +57,10 @@ fn parse_variable_declaration() {
 #[test]
 fn parse_if_else_statement() {
     let src = "int main() { if (1) { return 1; } else { return 0; } }";
-    let mut parser = Parser::new(lex(src));
+    let mut parser = Parser::new(lex(src), layout());
     let program = parser.parse_program().expect("Failed to parse if-else");
     assert_eq!(program.decls.len(), 1);
-    if let Decl::Function { body, .. } = &program.decls[0] {
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
         assert_eq!(body.len(), 1);
         if let Stmt::If { cond, then_block, else_block } = &body[0] {
             match **cond {
@@ -72,10 +81,10 @@ fn parse_if_else_statement() {
 #[test]
 fn parse_while_loop() {
     let src = "int main() { while (0) { x = x + 1; } }";
-    let mut parser = Parser::new(lex(src));
+    let mut parser = Parser::new(lex(src), layout());
     let program = parser.parse_program().expect("Failed to parse while");
     assert_eq!(program.decls.len(), 1);
-    if let Decl::Function { body, .. } = &program.decls[0] {
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
         assert_eq!(body.len(), 1);
         if let Stmt::While(cond, stmts) = &body[0] {
             match **cond {
@@ -92,13 +101,13 @@ fn parse_while_loop() {
 #[test]
 fn parse_for_loop() {
     let src = "int main() { for (i=0; i<10; i=i+1) { ; } }";
-    let mut parser = Parser::new(lex(src));
+    let mut parser = Parser::new(lex(src), layout());
     let program = parser.parse_program().expect("Failed to parse for");
     assert_eq!(program.decls.len(), 1);
-    if let Decl::Function { body, .. } = &program.decls[0] {
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
         assert_eq!(body.len(), 1);
         if let Stmt::For { init, cond, inc, body: stmts } = &body[0] {
-            assert!(init.is_some());
+            assert!(matches!(init.as_slice(), [Stmt::Expr(_)]));
             assert!(cond.is_some());
             assert!(inc.is_some());
             assert_eq!(stmts.len(), 1);
@@ -111,10 +120,10 @@ fn parse_for_loop() {
 #[test]
 fn parse_expression_precedence() {
     let src = "int main() { return 1 + 2 * 3; }";
-    let mut parser = Parser::new(lex(src));
+    let mut parser = Parser::new(lex(src), layout());
     let program = parser.parse_program().expect("Failed to parse expr");
-    if let Decl::Function { body, .. } = &program.decls[0] {
-        if let Stmt::Return(expr) = &body[0] {
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::Return(Some(expr), _) = &body[0] {
             if let Expr::Binary { op, left, right, .. } = &**expr {
                 assert_eq!(*op, TokenKind::Plus);
                 if let Expr::IntLiteral { value: l, .. } = **left {
@@ -139,7 +148,7 @@ fn parse_expression_precedence() {
 #[test]
 fn parse_return_without_semicolon_error() {
     let src = "int main() { return 1 }";
-    let mut parser = Parser::new(lex(src));
+    let mut parser = Parser::new(lex(src), layout());
     let err = parser.parse_program().unwrap_err();
     match err {
         ParseError::Expected { expected, found, span: _ } => {
@@ -149,3 +158,638 @@ fn parse_return_without_semicolon_error() {
         _ => panic!("Expected semicolon error"),
     }
 }
+
+#[test]
+fn parse_function_params_and_call() {
+    let src = "int add(int a, int b) { return add(a, b + 1); }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse params");
+    if let Decl::Function { params, body: Some(body), .. } = &program.decls[0] {
+        assert_eq!(params, &vec![("a".to_string(), Type::Int), ("b".to_string(), Type::Int)]);
+        if let Stmt::Return(Some(expr), _) = &body[0] {
+            if let Expr::Call { callee, args, .. } = &**expr {
+                assert_eq!(callee, "add");
+                assert_eq!(args.len(), 2);
+                assert!(matches!(args[1], Expr::Binary { op: TokenKind::Plus, .. }));
+            } else {
+                panic!("Expected call expression");
+            }
+        }
+    } else {
+        panic!("Expected function declaration");
+    }
+}
+
+#[test]
+fn parse_logical_operator_precedence() {
+    let src = "int main() { return !a || b && c == d; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse logical operators");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::Return(Some(expr), _) = &body[0] {
+            if let Expr::Binary { op, left, right, .. } = &**expr {
+                assert_eq!(*op, TokenKind::LogicalOr);
+                assert!(matches!(**left, Expr::Unary { op: TokenKind::LogicalNot, .. }));
+                if let Expr::Binary { op: and_op, right: and_right, .. } = &**right {
+                    assert_eq!(*and_op, TokenKind::LogicalAnd);
+                    assert!(matches!(**and_right, Expr::Binary { op: TokenKind::Equal, .. }));
+                } else { panic!("Right side not `&&`"); }
+            } else {
+                panic!("Expected binary expression");
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_unary_operators() {
+    let src = "int g = -5; int main() { return -x + ~1; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse unary operators");
+    match &program.decls[0] {
+        Decl::Var { init: Some(init), .. } => assert!(matches!(**init, Expr::IntLiteral { value: -5, .. })),
+        _ => panic!("Expected a variable declaration"),
+    }
+    if let Decl::Function { body: Some(body), .. } = &program.decls[1] {
+        if let Stmt::Return(Some(expr), _) = &body[0] {
+            if let Expr::Binary { op, left, right, .. } = &**expr {
+                assert_eq!(*op, TokenKind::Plus);
+                assert!(matches!(**left, Expr::Unary { op: TokenKind::Minus, .. }));
+                assert!(matches!(**right, Expr::IntLiteral { value: -2, .. }));
+            } else {
+                panic!("Expected binary expression");
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_void_function_with_bare_return() {
+    let src = "void reset(void) { return; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse void function");
+    if let Decl::Function { ret_ty, params, body: Some(body), .. } = &program.decls[0] {
+        assert_eq!(*ret_ty, Type::Void);
+        assert!(params.is_empty());
+        assert!(matches!(body[0], Stmt::Return(None, _)));
+    } else {
+        panic!("Expected function declaration");
+    }
+}
+
+#[test]
+fn parse_do_while_loop() {
+    let src = "int main() { do { x = x + 1; } while (x < 10); }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse do-while");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::DoWhile(stmts, cond) = &body[0] {
+            assert_eq!(stmts.len(), 1);
+            assert!(matches!(**cond, Expr::Binary { op: TokenKind::Less, .. }));
+        } else {
+            panic!("Expected do-while statement");
+        }
+    }
+}
+
+#[test]
+fn parse_switch_statement() {
+    let src = "int main() { switch (x) { case 1: case -2: x = 0; break; default: x = 1; } }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse switch");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::Switch { body: stmts, .. } = &body[0] {
+            assert_eq!(stmts.len(), 6);
+            assert!(matches!(&stmts[1], Stmt::Case(value, _) if matches!(**value, Expr::IntLiteral { value: -2, .. })));
+            assert!(matches!(stmts[3], Stmt::Break(_)));
+            assert!(matches!(stmts[4], Stmt::Default(_)));
+        } else {
+            panic!("Expected switch statement");
+        }
+    }
+}
+
+#[test]
+fn parse_single_statement_bodies_and_else_if() {
+    let src = "int main() { if (a) return 1; else if (b) return 2; else return 3; while (x) x = x - 1; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse single-statement bodies");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        assert_eq!(body.len(), 2);
+        if let Stmt::If { then_block, else_block, .. } = &body[0] {
+            assert!(matches!(then_block[..], [Stmt::Return(Some(_), _)]));
+            let else_blk = else_block.as_ref().expect("Expected else branch");
+            assert!(matches!(else_blk[..], [Stmt::If { else_block: Some(_), .. }]));
+        } else {
+            panic!("Expected if statement");
+        }
+        assert!(matches!(&body[1], Stmt::While(_, stmts) if stmts.len() == 1));
+    }
+}
+
+#[test]
+fn parse_dangling_else_binds_to_nearest_if() {
+    let src = "int main() { if (a) if (b) return 1; else return 2; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse dangling else");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::If { then_block, else_block, .. } = &body[0] {
+            assert!(else_block.is_none(), "Outer if must not own the else");
+            assert!(matches!(then_block[..], [Stmt::If { else_block: Some(_), .. }]));
+        } else {
+            panic!("Expected if statement");
+        }
+    }
+}
+
+#[test]
+fn parse_compound_assignment_and_increments() {
+    let src = "int main() { x += y -= 2; ++x; x--; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse compound assignment");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::Expr(expr) = &body[0] {
+            if let Expr::Binary { op, right, .. } = &**expr {
+                assert_eq!(*op, TokenKind::PlusAssign);
+                assert!(matches!(**right, Expr::Binary { op: TokenKind::MinusAssign, .. }), "Expected right associativity");
+            } else { panic!("Expected compound assignment"); }
+        }
+        assert!(matches!(&body[1], Stmt::Expr(e) if matches!(**e, Expr::Unary { op: TokenKind::PlusPlus, .. })));
+        assert!(matches!(&body[2], Stmt::Expr(e) if matches!(**e, Expr::Postfix { op: TokenKind::MinusMinus, .. })));
+    }
+}
+
+#[test]
+fn parse_bitwise_precedence_levels() {
+    // Each operator binds tighter than the one before it, so the tree leans right.
+    let src = "int main() { return a | b ^ c & d == e << 1 + 2 % 3; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse bitwise operators");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::Return(Some(expr), _) = &body[0] {
+            let mut ops = Vec::new();
+            let mut cur = &**expr;
+            while let Expr::Binary { op, right, .. } = cur {
+                ops.push(op.clone());
+                cur = right;
+            }
+            assert_eq!(
+                ops,
+                vec![
+                    TokenKind::Pipe,
+                    TokenKind::Caret,
+                    TokenKind::Ampersand,
+                    TokenKind::Equal,
+                    TokenKind::ShiftLeft,
+                    TokenKind::Plus,
+                    TokenKind::Percent,
+                ]
+            );
+        }
+    }
+}
+
+#[test]
+fn parse_conditional_right_associative() {
+    let src = "int main() { x = a ? b : c ? d : e; return x; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse conditional");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::Expr(expr) = &body[0] {
+            if let Expr::Binary { op: TokenKind::Assign, right, .. } = &**expr {
+                if let Expr::Conditional { cond, else_expr, .. } = &**right {
+                    assert!(matches!(&**cond, Expr::Var { name, .. } if name == "a"));
+                    assert!(matches!(&**else_expr, Expr::Conditional { .. }));
+                } else {
+                    panic!("Expected conditional on the right of '=', got {:?}", right);
+                }
+            } else {
+                panic!("Expected assignment, got {:?}", expr);
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_multiple_declarators_without_initializers() {
+    let src = "int a = 1, b, c = 3; int main() { int x; int y = 2, z; return a; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse declarators");
+    let globals: Vec<(&str, bool)> = program.decls.iter().filter_map(|d| match d {
+        Decl::Var { name, init, .. } => Some((name.as_str(), init.is_some())),
+        _ => None,
+    }).collect();
+    assert_eq!(globals, vec![("a", true), ("b", false), ("c", true)]);
+    if let Decl::Function { body: Some(body), .. } = &program.decls[3] {
+        let locals: Vec<(&str, bool)> = body.iter().filter_map(|s| match s {
+            Stmt::LocalVar { name, init, .. } => Some((name.as_str(), init.is_some())),
+            _ => None,
+        }).collect();
+        assert_eq!(locals, vec![("x", false), ("y", true), ("z", false)]);
+    } else {
+        panic!("Expected function after globals");
+    }
+}
+
+#[test]
+fn parse_for_with_declaration() {
+    let src = "int main() { for (int i = 0, j = 10; i < j; i = i + 1) ; return 0; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse for with declaration");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::For { init, .. } = &body[0] {
+            assert!(matches!(init.as_slice(), [Stmt::LocalVar { .. }, Stmt::LocalVar { .. }]));
+        } else {
+            panic!("Expected for statement");
+        }
+    }
+}
+
+#[test]
+fn parse_char_literal_escapes() {
+    let src = r"char a = 'a', b = '\n', c = '\0', d = '\\', e = '\'', f = '\101', g = '\x41', h = '\xff';";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse char literals");
+    let values: Vec<i64> = program.decls.iter().map(|d| match d {
+        Decl::Var { ty: Type::Char, init: Some(init), .. } => match **init {
+            Expr::IntLiteral { value, .. } => value,
+            _ => panic!("Expected literal initializer, got {:?}", init),
+        },
+        other => panic!("Expected char declaration, got {:?}", other),
+    }).collect();
+    assert_eq!(values, vec![97, 10, 0, 92, 39, 65, 65, -1]);
+}
+
+#[test]
+fn parse_error_invalid_char_literal() {
+    for src in [r"int x = 'ab';", r"int x = '\q';", r"int x = '\x100';"] {
+        let mut parser = Parser::new(lex(src), layout());
+        assert!(parser.parse_program().is_err(), "Expected error for {}", src);
+    }
+}
+
+#[test]
+fn parse_string_literal_concatenation() {
+    let src = r#"int main() { "ab\n" "c\x41\0"; return 0; }"#;
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse string literal");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        match &body[0] {
+            Stmt::Expr(expr) => match &**expr {
+                Expr::StringLiteral { value, .. } => assert_eq!(value, b"ab\ncA\0"),
+                other => panic!("Expected string literal, got {:?}", other),
+            },
+            other => panic!("Expected expression statement, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn parse_prototypes() {
+    let src = "int putchar(int); int printf(char *fmt, ...); void *malloc(int size);";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse prototypes");
+    match &program.decls[0] {
+        Decl::Function { params, variadic: false, body: None, .. } => assert_eq!(params, &vec![(String::new(), Type::Int)]),
+        other => panic!("Expected putchar prototype, got {:?}", other),
+    }
+    match &program.decls[1] {
+        Decl::Function { params, variadic: true, body: None, .. } => {
+            assert_eq!(params, &vec![("fmt".to_string(), Type::Pointer(Box::new(Type::Char)))]);
+        }
+        other => panic!("Expected variadic printf prototype, got {:?}", other),
+    }
+    match &program.decls[2] {
+        Decl::Function { ret_ty, body: None, .. } => assert_eq!(ret_ty, &Type::Pointer(Box::new(Type::Void))),
+        other => panic!("Expected malloc prototype, got {:?}", other),
+    }
+}
+
+#[test]
+fn parse_address_of_and_dereference() {
+    let src = "int main() { **pp = a * *&b; return 0; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse pointer operators");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::Expr(expr) = &body[0] {
+            if let Expr::Binary { op: TokenKind::Assign, left, right, .. } = &**expr {
+                assert!(matches!(&**left, Expr::Unary { op: TokenKind::Star, operand, .. }
+                    if matches!(&**operand, Expr::Unary { op: TokenKind::Star, .. })));
+                if let Expr::Binary { op: TokenKind::Star, right, .. } = &**right {
+                    assert!(matches!(&**right, Expr::Unary { op: TokenKind::Star, operand, .. }
+                        if matches!(&**operand, Expr::Unary { op: TokenKind::Ampersand, .. })));
+                } else {
+                    panic!("Expected multiplication, got {:?}", right);
+                }
+            } else {
+                panic!("Expected assignment, got {:?}", expr);
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_array_declarators_and_initializers() {
+    let src = "int m[2][3] = {{1, 2, 3}, {4}}; char s[] = \"hi\"; int v[] = {1, 2, 3,}; int sum(int a[], int n);";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse arrays");
+    let int_array = |n| Type::Array(Box::new(Type::Int), n);
+    match &program.decls[0] {
+        Decl::Var { ty, init: Some(init), .. } => {
+            assert_eq!(ty, &Type::Array(Box::new(int_array(3)), 2));
+            assert!(matches!(&**init, Expr::InitList { items, .. } if items.len() == 2));
+        }
+        other => panic!("Expected matrix declaration, got {:?}", other),
+    }
+    assert!(matches!(&program.decls[1], Decl::Var { ty: Type::Array(_, 3), .. }));
+    assert!(matches!(&program.decls[2], Decl::Var { ty, .. } if *ty == int_array(3)));
+    match &program.decls[3] {
+        Decl::Function { params, .. } => assert_eq!(params[0].1, Type::Pointer(Box::new(Type::Int))),
+        other => panic!("Expected prototype, got {:?}", other),
+    }
+}
+
+#[test]
+fn parse_brace_elided_initializers() {
+    let src = "struct p { int x; int y; }; int m[2][2] = {1, 2, 3, 4}; int n[][3] = {1, 2, 3, 4}; struct p ps[] = {1, 2, {3}, 4};";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse initializers");
+    let lengths = |init: &Expr| match init {
+        Expr::InitList { items, .. } => items.iter().map(|item| match item {
+            Expr::InitList { items, .. } => items.len(),
+            other => panic!("Expected braced row, got {:?}", other),
+        }).collect::<Vec<_>>(),
+        other => panic!("Expected initializer list, got {:?}", other),
+    };
+    let int_array = |n| Type::Array(Box::new(Type::Int), n);
+    match &program.decls[1] {
+        Decl::Var { init: Some(init), .. } => assert_eq!(lengths(init), [2, 2]),
+        other => panic!("Expected matrix declaration, got {:?}", other),
+    }
+    match &program.decls[2] {
+        Decl::Var { ty, init: Some(init), .. } => {
+            assert_eq!(ty, &Type::Array(Box::new(int_array(3)), 2));
+            assert_eq!(lengths(init), [3, 1]);
+        }
+        other => panic!("Expected open matrix declaration, got {:?}", other),
+    }
+    match &program.decls[3] {
+        Decl::Var { ty, init: Some(init), .. } => {
+            assert_eq!(ty, &Type::Array(Box::new(Type::Struct("p".into())), 3));
+            assert_eq!(lengths(init), [2, 1, 1]);
+        }
+        other => panic!("Expected struct array declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn parse_index_chain() {
+    let src = "int main() { return m[i][j + 1]++; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse subscripts");
+    if let Decl::Function { body: Some(body), .. } = &program.decls[0] {
+        if let Stmt::Return(Some(expr), _) = &body[0] {
+            if let Expr::Postfix { operand, .. } = &**expr {
+                if let Expr::Index { base, index, .. } = &**operand {
+                    assert!(matches!(&**base, Expr::Index { .. }));
+                    assert!(matches!(&**index, Expr::Binary { op: TokenKind::Plus, .. }));
+                    return;
+                }
+            }
+            panic!("Expected postfix increment of a subscript, got {:?}", expr);
+        }
+    }
+    panic!("Expected return statement");
+}
+
+#[test]
+fn parse_error_non_constant_array_size() {
+    let src = "int main() { int a[n]; return 0; }";
+    let mut parser = Parser::new(lex(src), layout());
+    assert!(matches!(parser.parse_program(), Err(ParseError::Expected { expected, .. }) if expected.contains("array size")));
+}
+
+#[test]
+fn parse_struct_definition_and_members() {
+    let src = "struct point { int x, y; struct point *next; } origin; int main() { return p->next.x; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse struct");
+    match &program.decls[0] {
+        Decl::Struct { name, members, .. } => {
+            assert_eq!(name, "point");
+            let names: Vec<&str> = members.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, vec!["x", "y", "next"]);
+            assert_eq!(members[2].1, Type::Pointer(Box::new(Type::Struct("point".into()))));
+        }
+        other => panic!("Expected struct definition, got {:?}", other),
+    }
+    assert!(matches!(&program.decls[1], Decl::Var { ty: Type::Struct(tag), .. } if tag == "point"));
+    if let Decl::Function { body: Some(body), .. } = &program.decls[2] {
+        if let Stmt::Return(Some(expr), _) = &body[0] {
+            if let Expr::Member { base, member, arrow: false, .. } = &**expr {
+                assert_eq!(member, "x");
+                assert!(matches!(&**base, Expr::Member { arrow: true, member, .. } if member == "next"));
+                return;
+            }
+        }
+    }
+    panic!("Expected member access in main");
+}
+
+#[test]
+fn parse_integer_types_and_literal_types() {
+    let src = "unsigned long a = 10ul; long unsigned int b = 0xffffffff; short c = 2147483648; signed char d = -1u;";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse integer types");
+    let decls: Vec<(&Type, &Expr)> = program
+        .decls
+        .iter()
+        .map(|decl| match decl {
+            Decl::Var { ty, init: Some(init), .. } => (ty, &**init),
+            other => panic!("Expected initialized variable, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(*decls[0].0, Type::ULong);
+    assert_eq!(*decls[1].0, Type::ULong);
+    assert_eq!(*decls[2].0, Type::Short);
+    assert_eq!(*decls[3].0, Type::Char);
+    assert!(matches!(decls[0].1, Expr::IntLiteral { value: 10, ty: Type::ULong, .. }));
+    // Hexadecimal literals may take an unsigned type before a wider signed one.
+    assert!(matches!(decls[1].1, Expr::IntLiteral { value: 0xffffffff, ty: Type::UInt, .. }));
+    assert!(matches!(decls[2].1, Expr::IntLiteral { value: 2147483648, ty: Type::Long, .. }));
+    assert!(matches!(decls[3].1, Expr::IntLiteral { value: -1, ty: Type::UInt, .. }));
+}
+
+#[test]
+fn parse_error_invalid_type_specifiers() {
+    for src in ["short long x;", "unsigned signed x;", "int int x;", "long long long x;"] {
+        let mut parser = Parser::new(lex(src), layout());
+        assert!(
+            matches!(parser.parse_program(), Err(ParseError::Expected { expected, .. }) if expected.contains("type specifiers")),
+            "Expected type specifier error for {}",
+            src
+        );
+    }
+}
+
+#[test]
+fn parse_float_literal_types() {
+    let src = "float f = 2.5f; double d = -1e3;";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse float literals");
+    match (&program.decls[0], &program.decls[1]) {
+        (Decl::Var { ty: Type::Float, init: Some(f), .. }, Decl::Var { ty: Type::Double, init: Some(d), .. }) => {
+            assert!(matches!(**f, Expr::FloatLiteral { value, ty: Type::Float, .. } if value == 2.5));
+            assert!(matches!(**d, Expr::FloatLiteral { value, ty: Type::Double, .. } if value == -1000.0));
+        }
+        other => panic!("Expected float and double variables, got {:?}", other),
+    }
+}
+
+#[test]
+fn parse_casts_and_sizeof() {
+    let src = "int main() { return (char *)p - (x) + sizeof(int *[2]) + sizeof a[0]; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse casts");
+    let Decl::Function { body: Some(body), .. } = &program.decls[0] else { panic!("Expected main") };
+    let Stmt::Return(Some(expr), _) = &body[0] else { panic!("Expected return, got {:?}", body[0]) };
+    let Expr::Binary { left, right: sizeof_expr, .. } = &**expr else { panic!("Expected sum, got {:?}", expr) };
+    assert!(matches!(&**sizeof_expr, Expr::SizeofExpr { expr, .. } if matches!(**expr, Expr::Index { .. })));
+    let Expr::Binary { left, right: sizeof_ty, .. } = &**left else { panic!("Expected sum, got {:?}", left) };
+    let int_ptrs = Type::Array(Box::new(Type::Pointer(Box::new(Type::Int))), 2);
+    assert!(matches!(&**sizeof_ty, Expr::SizeofType { ty, .. } if *ty == int_ptrs));
+    let Expr::Binary { left: cast, right: paren, .. } = &**left else { panic!("Expected difference, got {:?}", left) };
+    assert!(matches!(&**cast, Expr::Cast { ty: Type::Pointer(inner), expr, .. } if **inner == Type::Char && matches!(**expr, Expr::Var { .. })));
+    assert!(matches!(&**paren, Expr::Var { name, .. } if name == "x"));
+}
+
+#[test]
+fn parse_typedef_name_disambiguates_declarations() {
+    let src = "typedef int T; int main() { T * x; int a; a * a; { int T; T * a; } return 0; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse typedef");
+    assert!(matches!(&program.decls[0], Decl::Typedef { name, ty: Type::Int, .. } if name == "T"));
+    let Decl::Function { body: Some(body), .. } = &program.decls[1] else { panic!("Expected main") };
+    let int_ptr = Type::Pointer(Box::new(Type::Int));
+    assert!(matches!(&body[0], Stmt::LocalVar { name, ty, .. } if name == "x" && *ty == int_ptr));
+    assert!(matches!(&body[2], Stmt::Expr(e) if matches!(**e, Expr::Binary { op: TokenKind::Star, .. })));
+    // Inside the block the variable `T` hides the typedef, so `T * a` is a multiplication.
+    let Stmt::Block(inner) = &body[3] else { panic!("Expected block, got {:?}", body[3]) };
+    assert!(matches!(&inner[1], Stmt::Expr(e) if matches!(**e, Expr::Binary { op: TokenKind::Star, .. })));
+}
+
+#[test]
+fn parse_enum_constants_and_anonymous_tags() {
+    let src = "enum color { RED, GREEN = 5, BLUE, LAST = BLUE * 2 }; \
+               typedef struct { int x; } point; union { int i; char c; } u; int a[LAST];";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse enum");
+    let Decl::Enum { name: Some(name), constants, .. } = &program.decls[0] else { panic!("Expected enum, got {:?}", program.decls[0]) };
+    assert_eq!(name, "color");
+    let expected: Vec<(String, i64)> = [("RED", 0), ("GREEN", 5), ("BLUE", 6), ("LAST", 12)].iter().map(|(n, v)| (n.to_string(), *v)).collect();
+    assert_eq!(*constants, expected);
+    assert!(matches!(&program.decls[1], Decl::Struct { name, is_union: false, .. } if name == "anon.1"));
+    assert!(matches!(&program.decls[2], Decl::Typedef { ty: Type::Struct(tag), .. } if tag == "anon.1"));
+    assert!(matches!(&program.decls[3], Decl::Struct { name, is_union: true, .. } if name == "anon.2"));
+    assert!(matches!(&program.decls[4], Decl::Var { ty: Type::Union(tag), .. } if tag == "anon.2"));
+    assert!(matches!(&program.decls[5], Decl::Var { ty: Type::Array(_, 12), .. }));
+}
+
+#[test]
+fn parse_error_enum_value_not_constant() {
+    let mut parser = Parser::new(lex("int x; enum e { A = x };"), layout());
+    match parser.parse_program() {
+        Err(ParseError::Expected { expected, .. }) => assert!(expected.contains("integer constant enumerator value")),
+        other => panic!("Expected enumerator error, got {:?}", other),
+    }
+}
+
+#[test]
+fn parse_qualifiers_and_storage_classes() {
+    let src = "static const int a = 1; extern char *const volatile p; int const *q; \
+               static int f(void) { static int n; extern int a; return n; }";
+    let mut parser = Parser::new(lex(src), layout());
+    let program = parser.parse_program().expect("Failed to parse storage classes");
+    let konst = Qualifiers { is_const: true, is_volatile: false };
+    let both = Qualifiers { is_const: true, is_volatile: true };
+    assert!(matches!(&program.decls[0], Decl::Var { ty, storage: Some(StorageClass::Static), .. } if *ty == Type::Int.qualified(konst)));
+    let char_ptr = Type::Pointer(Box::new(Type::Char)).qualified(both);
+    assert!(matches!(&program.decls[1], Decl::Var { ty, storage: Some(StorageClass::Extern), .. } if *ty == char_ptr));
+    let const_int_ptr = Type::Pointer(Box::new(Type::Int.qualified(konst)));
+    assert!(matches!(&program.decls[2], Decl::Var { ty, storage: None, .. } if *ty == const_int_ptr));
+    let Decl::Function { storage: Some(StorageClass::Static), body: Some(body), .. } = &program.decls[3] else {
+        panic!("Expected static function, got {:?}", program.decls[3])
+    };
+    assert!(matches!(&body[0], Stmt::LocalVar { name, storage: Some(StorageClass::Static), .. } if name == "n"));
+    assert!(matches!(&body[1], Stmt::LocalVar { name, storage: Some(StorageClass::Extern), .. } if name == "a"));
+}
+
+#[test]
+fn parse_sizeof_in_array_size() {
+    let src = "struct s { char c; long l; short h; };
+               char b[sizeof(long)];
+               int arr[] = {1, 2, 3};
+               int counts[sizeof arr / sizeof arr[0]];
+               enum { N = sizeof(struct s) };
+               char padded[N];";
+    let program = Parser::new(lex(src), layout()).parse_program().expect("Failed to parse sizeof in array sizes");
+    let sizes: Vec<usize> = program.decls.iter().filter_map(|decl| match decl {
+        Decl::Var { ty: Type::Array(_, n), .. } => Some(*n),
+        _ => None,
+    }).collect();
+    assert_eq!(sizes, vec![8, 3, 3, 24]);
+}
+
+#[test]
+fn parse_sizeof_of_computed_operands() {
+    let src = "struct s { char c; short h; }; long twice(long x); char c; double d; int a[4]; struct s *p;
+               char b[sizeof(a[0] + 1)];
+               char e[sizeof(c + c)];
+               char f[sizeof(twice(1))];
+               char g[sizeof(c ? d : 1)];
+               char h[sizeof((char)p->h)];
+               char i[sizeof(&a[0] - &a[1])];
+               char j[sizeof(-c)];
+               char k[sizeof(2[a])];
+               char l[sizeof(c = 5)];
+               char m[sizeof(a + 1)];";
+    let program = Parser::new(lex(src), layout()).parse_program().expect("Failed to parse sizeof of expressions");
+    let sizes: Vec<usize> = program.decls.iter().skip(6).filter_map(|decl| match decl {
+        Decl::Var { ty: Type::Array(_, n), .. } => Some(*n),
+        _ => None,
+    }).collect();
+    assert_eq!(sizes, vec![4, 4, 8, 8, 1, 8, 4, 4, 1, 8]);
+}
+
+#[test]
+fn parse_block_scope_tag_definitions() {
+    let src = "struct s { int a; };
+               int main() {
+                   enum { N = 4 };
+                   int a[N];
+                   struct s { char c; } v;
+                   { struct s { long l; } w; }
+                   struct s *p = &v;
+                   return N;
+               }
+               struct s g;";
+    let program = Parser::new(lex(src), layout()).parse_program().expect("Failed to parse block-scope tags");
+    let tags: Vec<&str> = program.decls.iter().filter_map(|decl| match decl {
+        Decl::Struct { name, .. } => Some(name.as_str()),
+        _ => None,
+    }).collect();
+    assert_eq!(tags.len(), 3);
+    assert_eq!(tags[0], "s");
+    assert!(tags[1] != "s" && tags[2] != "s" && tags[1] != tags[2], "Expected unique block tags, got {:?}", tags);
+    let Some(Decl::Function { body: Some(body), .. }) = program.decls.iter().find(|decl| matches!(decl, Decl::Function { .. })) else {
+        panic!("Expected main");
+    };
+    let locals: Vec<&Type> = body.iter().filter_map(|stmt| match stmt {
+        Stmt::LocalVar { ty, .. } => Some(ty),
+        _ => None,
+    }).collect();
+    assert_eq!(locals[0], &Type::Array(Box::new(Type::Int), 4));
+    assert_eq!(locals[1], &Type::Struct(tags[1].to_string()));
+    assert_eq!(locals[2], &Type::Pointer(Box::new(Type::Struct(tags[1].to_string()))));
+    assert!(matches!(program.decls.last(), Some(Decl::Var { ty: Type::Struct(tag), .. }) if tag == "s"));
+}
@@ -30,6 +30,21 @@ fn semantic_error_duplicate_global_symbol() {
     }
 }
 
+#[test]
+fn semantic_tentative_definitions_and_unsized_extern_arrays() {
+    assert!(compile("int x; int x; int y; int y = 3; int y; extern int a[]; int a[4]; extern int a[]; int n = sizeof a;").is_ok());
+    for (src, expected) in [
+        ("int x; int x = 1; int x = 2;", "Duplicate symbol 'x'"),
+        ("extern int a[]; int a[4]; extern long a[];", "Conflicting types for 'a'"),
+        ("extern int a[]; int f() { return sizeof a; }", "Incomplete type"),
+    ] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => assert!(err.to_string().contains(expected), "Unexpected message for {}: {}", src, err),
+            other => panic!("Expected error for {}, got {:?}", src, other),
+        }
+    }
+}
+
 #[test]
 fn semantic_error_duplicate_local_variable() {
     let src = "int main() { int x = 1; int x = 2; return 0; }";
@@ -104,3 +119,566 @@ fn semantic_error_undefined_in_for_loop() {
         other => panic!("Expected undefined for-loop variable error, got {:?}", other),
     }
 }
+
+#[test]
+fn semantic_ok_params_and_forward_call() {
+    let src = "int main() { return add(1, 2); } int add(int a, int b) { return a + b; }";
+    assert!(compile(src).is_ok());
+}
+
+#[test]
+fn semantic_error_argument_count() {
+    let src = "int f(int a) { return a; } int main() { return f(1, 2); }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Function 'f' expects 1 argument(s), found 2"));
+        }
+        other => panic!("Expected argument count error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_ok_unprototyped_declaration() {
+    assert!(compile("int f(); int main() { return f(1, 2) + f(); }").is_ok());
+    assert!(compile("int f(); int f(int a, int b) { return a + b; } int main() { return f(1, 2); }").is_ok());
+    // Once a prototype is in scope, calls are checked against it.
+    match compile("int f(); int f(int a); int main() { return f(1, 2); }") {
+        Err(CompilerError::Semantic(err)) => assert!(err.to_string().contains("Function 'f' expects 1 argument(s), found 2")),
+        other => panic!("Expected argument count error, got {:?}", other),
+    }
+    match compile("int f() { return 0; } int main() { return f(1); }") {
+        Err(CompilerError::Semantic(err)) => assert!(err.to_string().contains("Function 'f' expects 0 argument(s), found 1")),
+        other => panic!("Expected argument count error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_undefined_function() {
+    let src = "int main() { return g(); }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Undefined function 'g'"));
+        }
+        other => panic!("Expected undefined function error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_short_circuit_constant_expressions() {
+    assert!(compile("int a[1 || 1 / 0]; int b[1 + (0 && 1 / 0)]; int f(int x) { switch (x) { case 0 && 1 / 0: return 1; case 1 ? 2 : 1 / 0: return 2; } return 0; }").is_ok());
+    match compile("int f(int x) { switch (x) { case 0 && x: return 1; } return 0; }") {
+        Err(CompilerError::Semantic(err)) => assert!(err.to_string().contains("not an integer constant expression")),
+        other => panic!("Expected non-constant case error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_non_constant_global_initializer() {
+    let src = "int x = 1; int y = x + 1;";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Initializer of global 'y' is not a constant expression"));
+        }
+        other => panic!("Expected non-constant initializer error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_ok_address_constant_initializers() {
+    let src = "int x; int *p = &x; int arr[4]; int *q = arr; int *r = &arr[1]; int *t = arr + 3 - 1; char *s = \"abc\" + 1; \
+               int f() { static int n; static int *m = &n; return *m; }";
+    assert!(compile(src).is_ok());
+    for src in ["int f() { int n; static int *m = &n; return *m; }", "int x; int *p = &x + x;"] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => assert!(err.to_string().contains("not a constant expression"), "Unexpected message for {}: {}", src, err),
+            other => panic!("Expected non-constant initializer error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn semantic_ok_void_function() {
+    let src = "int n = 0; void inc() { n = n + 1; return; } int main() { inc(); return n; }";
+    assert!(compile(src).is_ok());
+}
+
+#[test]
+fn semantic_error_return_value_in_void_function() {
+    let src = "void f() { return 1; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Function 'f' returns void but a value is returned"));
+        }
+        other => panic!("Expected void return error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_bare_return_in_int_function() {
+    let src = "int f() { return; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Function 'f' must return a value"));
+        }
+        other => panic!("Expected missing return value error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_void_call_used_as_value() {
+    let src = "void f() {} int main() { int x = f(); return x; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Type mismatch") && msg.contains("Void"));
+        }
+        other => panic!("Expected void value error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_ok_break_continue_in_loops() {
+    let src = "int main() { while (1) { if (1) { break; } continue; } for (;;) { break; } return 0; }";
+    assert!(compile(src).is_ok());
+}
+
+#[test]
+fn semantic_error_break_outside_loop() {
+    let src = "int main() { if (1) { break; } return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("'break' outside of a loop"));
+        }
+        other => panic!("Expected break outside loop error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_continue_outside_loop() {
+    let src = "int main() { continue; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("'continue' outside of a loop"));
+        }
+        other => panic!("Expected continue outside loop error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_do_while_body_scope_ends_before_condition() {
+    let src = "int main() { do { int y = 1; } while (y); return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Undefined variable 'y'"));
+        }
+        other => panic!("Expected undefined variable error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_duplicate_case_value() {
+    let src = "int main() { switch (1) { case 1 + 1: break; case 2: break; } return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Duplicate case value 2"));
+        }
+        other => panic!("Expected duplicate case error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_sizeof_case_label() {
+    assert!(compile("int f(int x) { int a[3]; switch (x) { case sizeof(int): return 1; case sizeof a: return 2; } return 0; }").is_ok());
+    assert!(compile("int f(int x) { int a[3]; switch (x) { case sizeof(x + 1): return 1; case sizeof(a[0] + 1L): return 2; } return 0; }").is_ok());
+    match compile("int f(int x) { switch (x) { case 4: break; case sizeof(int): break; } return 0; }") {
+        Err(CompilerError::Semantic(err)) => assert!(err.to_string().contains("Duplicate case value 4")),
+        other => panic!("Expected duplicate case error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_duplicate_case_after_conversion() {
+    let src = "int f(int x) { switch (x) { case 0: return 1; case 4294967296: return 2; } return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => assert!(err.to_string().contains("Duplicate case value 0"), "Unexpected message: {}", err),
+        other => panic!("Expected duplicate case error, got {:?}", other),
+    }
+    assert!(compile("int f(long x) { switch (x) { case 0: return 1; case 4294967296: return 2; } return 0; }").is_ok());
+}
+
+#[test]
+fn semantic_error_non_constant_case_label() {
+    let src = "int main() { int x = 1; switch (x) { case x: break; } return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Case label is not an integer constant expression"));
+        }
+        other => panic!("Expected non-constant case error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_continue_in_switch_without_loop() {
+    let src = "int main() { switch (1) { case 1: continue; } return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("'continue' outside of a loop"));
+        }
+        other => panic!("Expected continue outside loop error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_assignment_to_rvalue() {
+    for src in ["int main() { int x = 0; x + 1 = 2; return x; }", "int main() { 3++; return 0; }", "int main() { int x = 1; ++(x * 2); return x; }"] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains("Expression is not assignable"), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected lvalue error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn semantic_error_conditional_arm_mismatch() {
+    let src = "void f() {} int main() { return 1 ? 2 : f(); }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Type mismatch"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected type mismatch error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_for_declaration_out_of_scope() {
+    let src = "int main() { for (int i = 0; i < 3; i = i + 1) { int i = 5; } return i; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Undefined variable 'i'"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected loop variable to be out of scope, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_string_as_int() {
+    let src = r#"int main() { int x = "text"; return x; }"#;
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Type mismatch") && msg.contains("Pointer(Char)"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected type mismatch for string initializer, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_ok_prototype_then_definition() {
+    let src = "int twice(int x); int main() { return twice(2); } int twice(int n) { return n * 2; }";
+    assert!(compile(src).is_ok());
+}
+
+#[test]
+fn semantic_error_conflicting_prototype() {
+    let src = "int f(int a); char f(int a) { return 'a'; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Conflicting types for 'f'"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected conflicting types error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_function_redefinition() {
+    let src = "int f() { return 0; } int f() { return 1; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Duplicate symbol 'f'"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected duplicate definition error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_variadic_too_few_arguments() {
+    let src = r#"int printf(char *fmt, ...); int main() { printf(); return 0; }"#;
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Function 'printf' expects 1 argument(s), found 0"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected argument count error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_ok_pointers() {
+    let src = "int g; int *gp = 0; int main() { int x = 1; int *p = &x; int **pp = &p; void *v = p; p = v; \
+               **pp = *p + 1; p = p + 1 - 1; int n = p - &x; if (p && p != 0 && p >= &x) p++; return *p + n; }";
+    assert!(compile(src).is_ok(), "{:?}", compile(src));
+}
+
+#[test]
+fn semantic_error_address_of_rvalue() {
+    let src = "int main() { int x = 1; int *p = &(x + 1); return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Expression is not assignable"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected lvalue error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_dereference_non_pointer() {
+    for src in ["int main() { int x = 1; return *x; }", "int main() { int x; void *v = &x; return *v; }"] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains("Type mismatch") && msg.contains("pointer"), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected dereference error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn semantic_error_invalid_pointer_operands() {
+    for src in ["int main() { int x; int *p = &x; p + p; return 0; }", "int main() { int x; int *p = &x; char *c = 0; return p == c; }"] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains("Invalid operands"), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected invalid operands error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn semantic_error_integer_to_pointer() {
+    let src = "int main() { int x = 1; int *p = x; return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Type mismatch") && msg.contains("Pointer(Int)"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected type mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_ok_arrays() {
+    let src = "int g[4] = {1, 2}; char s[4] = \"abc\"; int first(int *p) { return p[0]; } \
+               int main() { int m[2][2] = {{1, 2}, {3}}; int *p = g; m[1][1] = first(g) + 2[g]; return *m[0] + p[1] + s[0]; }";
+    assert!(compile(src).is_ok(), "{:?}", compile(src));
+}
+
+#[test]
+fn semantic_error_excess_array_initializers() {
+    for src in ["int a[2] = {1, 2, 3};", "char s[2] = \"abc\";", "int m[2][2] = {1, 2, 3, 4, 5};"] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains("Excess elements in initializer"), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected excess initializer error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn semantic_error_assign_to_array() {
+    let src = "int main() { int a[3], b[3]; a = b; return 0; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("Expression is not assignable"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected lvalue error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_ok_struct_tags_and_members() {
+    let src = "struct point { int x; int y; }; struct point point = {1, 2}; \
+               int main() { struct point p = point, *pp = &p; p = point; pp->y = p.x; return (*pp).y; }";
+    assert!(compile(src).is_ok(), "{:?}", compile(src));
+}
+
+#[test]
+fn semantic_error_unknown_member() {
+    let src = "struct point { int x; }; int main() { struct point p; return p.y; }";
+    match compile(src) {
+        Err(CompilerError::Semantic(err)) => {
+            let msg = err.to_string();
+            assert!(msg.contains("has no member named 'y'"), "Unexpected message: {}", msg);
+        }
+        other => panic!("Expected unknown member error, got {:?}", other),
+    }
+}
+
+#[test]
+fn semantic_error_incomplete_struct() {
+    for src in ["struct s; struct s g;", "struct s { struct s inner; };"] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains("Incomplete type"), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected incomplete type error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn usual_arithmetic_conversions() {
+    use cmpler_core::lexer::TokenKind;
+    use cmpler_core::semantic::symbol_table::Type;
+    assert_eq!(Type::arithmetic(&Type::Char, &Type::UShort), Type::Int);
+    assert_eq!(Type::arithmetic(&Type::Int, &Type::UInt), Type::UInt);
+    assert_eq!(Type::arithmetic(&Type::UInt, &Type::Long), Type::Long);
+    assert_eq!(Type::arithmetic(&Type::ULong, &Type::LongLong), Type::ULongLong);
+    assert_eq!(Type::arithmetic(&Type::Long, &Type::Int), Type::Long);
+    assert_eq!(Type::binary_result(&TokenKind::ShiftRight, &Type::UShort, &Type::ULong), Type::Int);
+    let ptr = Type::Pointer(Box::new(Type::Int));
+    assert_eq!(Type::binary_result(&TokenKind::Minus, &ptr, &ptr), Type::Long);
+}
+
+#[test]
+fn semantic_ok_mixed_integer_types() {
+    let src = "unsigned long f(short s, unsigned char c, long long n) { unsigned u = s; n += c; return u * n; } \
+               int main() { int a[3]; long i = 2; unsigned j = 1; a[i] = a[j]; return f(1, 2, 3) > -1; }";
+    assert!(compile(src).is_ok(), "{:?}", compile(src));
+}
+
+#[test]
+fn semantic_ok_floating_conversions() {
+    let src = "double g = 1 / 2.0; int h = 2.5; \
+               float f(float x, int n) { double d = x * n; n = d; d++; return d > 0 && x ? d : -n; }";
+    assert!(compile(src).is_ok(), "{:?}", compile(src));
+}
+
+#[test]
+fn semantic_error_floating_operands_of_integer_operator() {
+    for src in ["int f(double d) { return d % 2; }", "int f(float x) { return x << 1; }", "int f(double d) { return ~d; }"] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains("expected \"integer\""), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected integer operand error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn semantic_ok_casts_and_sizeof() {
+    let src = "struct s { int x; }; long g = (long)2.5 + (char)300; \
+               int main() { struct s v; double d = 1; char *p = (char *)&v; long a = (long)p; \
+               (void)v; return (int)d + sizeof v + sizeof(struct s) + (int)sizeof p + a; }";
+    assert!(compile(src).is_ok(), "{:?}", compile(src));
+}
+
+#[test]
+fn semantic_error_invalid_cast_and_sizeof() {
+    for (src, expected) in [
+        ("struct s { int x; }; int main() { struct s v; return (int)v; }", "Cannot cast"),
+        ("int main() { double d; return (int *)d != 0; }", "Cannot cast"),
+        ("struct s; int main() { return sizeof(struct s); }", "Incomplete type"),
+        ("void f(); int main() { return sizeof f(); }", "Incomplete type"),
+    ] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains(expected), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn semantic_ok_typedef_enum_union() {
+    let src = "typedef unsigned long size; enum e { A, B = 4 }; union u { int i; double d; }; \
+               typedef union u U; int u; size n = B; union u g = { 1 }; int arr[B]; \
+               int main() { U v = { 2 }; enum e x = A; v.d = 1.5; switch (x) { case A: return 1; case B: return v.i; } return sizeof arr; }";
+    assert!(compile(src).is_ok(), "{:?}", compile(src));
+}
+
+#[test]
+fn semantic_error_namespace_conflicts() {
+    for (src, expected) in [
+        ("typedef int T; int T;", "Duplicate symbol 'T'"),
+        ("int T; typedef int T;", "Duplicate symbol 'T'"),
+        ("typedef int T; typedef long T;", "Conflicting types for 'T'"),
+        ("enum e { A }; int A;", "Duplicate symbol 'A'"),
+        ("struct s { int x; }; union s { int y; };", "Duplicate symbol 'union s'"),
+        ("enum e { A }; enum e { B };", "Duplicate symbol 'enum e'"),
+        ("union u { int i; char c; }; union u g = { 1, 2 };", "Excess elements"),
+        ("union u { int *p; }; union u g = { 1.5 };", "Type mismatch"),
+    ] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains(expected), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected error for {}, got {:?}", src, other),
+        }
+    }
+}
+
+#[test]
+fn semantic_ok_storage_classes_and_qualifiers() {
+    let src = "extern int x; extern int x; int x = 1; static int f(void); int f(void) { return x; } \
+               static int g; extern int g; const char *name = \"n\"; \
+               int count(void) { static int calls = 0; extern int x; volatile int v = calls; return ++calls + v; } \
+               int sum(const int *p) { const int k = 2; int a[2] = { k, k }; p = a; return p[0] + k; }";
+    assert!(compile(src).is_ok(), "{:?}", compile(src));
+}
+
+#[test]
+fn semantic_error_const_and_linkage() {
+    for (src, expected) in [
+        ("const int c = 1; int main() { c = 2; return 0; }", "read-only"),
+        ("int main() { const int c = 1; c++; return c; }", "read-only"),
+        ("int f(const int *p) { *p += 1; return 0; }", "read-only"),
+        ("int f(int *const p) { p = 0; return 0; }", "read-only"),
+        ("struct s { const int x; }; int f(struct s *a, struct s b) { *a = b; return 0; }", "read-only"),
+        ("struct s { int x; }; const struct s g = { 1 }; int main() { g.x = 2; return 0; }", "read-only"),
+        ("int f(const char *s) { char *t = s; return 0; }", "discards qualifiers"),
+        ("extern int x; static int x;", "Conflicting linkage for 'x'"),
+        ("int f(void); static int f(void);", "Conflicting linkage for 'f'"),
+        ("static int y; int y;", "Conflicting linkage for 'y'"),
+        ("int g(); int f() { static int n = g(); return n; }", "not a constant expression"),
+        ("int f() { extern int n = 1; return n; }", "Invalid initializer"),
+    ] {
+        match compile(src) {
+            Err(CompilerError::Semantic(err)) => {
+                let msg = err.to_string();
+                assert!(msg.contains(expected), "Unexpected message for {}: {}", src, msg);
+            }
+            other => panic!("Expected error for {}, got {:?}", src, other),
+        }
+    }
+}